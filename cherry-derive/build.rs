@@ -0,0 +1,4 @@
+// The derive reads `CHERRY_VERIFY`, the crates using it are rebuilt with the derive when it changes.
+fn main() {
+    println!("cargo:rerun-if-env-changed=CHERRY_VERIFY");
+}
@@ -0,0 +1,97 @@
+use heck::{CamelCase, KebabCase, MixedCase, ShoutySnakeCase, SnakeCase};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, LitStr};
+use syn::meta::ParseNestedMeta;
+
+// Walk the keys of every `#[cherry(..)]` attribute.
+pub(crate) fn parse_cherry<F>(attrs: &[Attribute], mut f: F) -> syn::Result<()>
+    where F: FnMut(ParseNestedMeta) -> syn::Result<()> {
+    attrs.iter()
+        .filter(|attr| attr.path().is_ident("cherry"))
+        .try_for_each(|attr| attr.parse_nested_meta(&mut f))
+}
+
+// The value of `key = "value"`.
+pub(crate) fn string(meta: &ParseNestedMeta) -> syn::Result<LitStr> {
+    meta.value()?.parse::<LitStr>()
+}
+
+pub(crate) fn unknown(meta: &ParseNestedMeta, expected: &[&str]) -> syn::Error {
+    let key = meta.path.get_ident().map(|ident| ident.to_string()).unwrap_or_default();
+    meta.error(format!("Unknown cherry attribute `{}`, expect one of: {}.", key, expected.join(", ")))
+}
+
+// Keep every error instead of stopping at the first one.
+pub(crate) fn collect<T, I>(results: I) -> syn::Result<Vec<T>>
+    where I: IntoIterator<Item = syn::Result<T>> {
+    let mut values = vec![];
+    let mut error: Option<syn::Error> = None;
+    for result in results {
+        match (result, &mut error) {
+            (Ok(value), _) => values.push(value),
+            (Err(e), Some(error)) => error.combine(e),
+            (Err(e), None) => error = Some(e),
+        }
+    }
+    error.map_or(Ok(values), Err)
+}
+
+const RENAME_RULES: &[&str] = &["snake_case", "camelCase", "PascalCase", "SCREAMING_SNAKE_CASE", "kebab-case"];
+
+pub(crate) fn rename_rule(lit: LitStr) -> syn::Result<String> {
+    let rule = lit.value();
+    match RENAME_RULES.contains(&rule.as_str()) {
+        true => Ok(rule),
+        _ => Err(syn::Error::new(
+            lit.span(),
+            format!("Unknown rename_all rule `{}`, expect one of: {}.", rule, RENAME_RULES.join(", ")),
+        )),
+    }
+}
+
+pub(crate) fn rename(name: &str, rule: Option<&str>) -> String {
+    match rule {
+        Some("snake_case") => name.to_snake_case(),
+        Some("camelCase") => name.to_mixed_case(),
+        Some("PascalCase") => name.to_camel_case(),
+        Some("SCREAMING_SNAKE_CASE") => name.to_shouty_snake_case(),
+        Some("kebab-case") => name.to_kebab_case(),
+        _ => name.to_string(),
+    }
+}
+
+// `database = "sqlite, mysql"`, every enabled database when absent.
+#[allow(unused_mut)]
+pub(crate) fn databases(lit: Option<LitStr>) -> syn::Result<Vec<&'static str>> {
+    let mut values = vec![];
+    if let Some(lit) = lit {
+        for value in lit.value().split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            values.push(match value {
+                "sqlite" => "sqlite",
+                "mysql" => "mysql",
+                "postgres" => "postgres",
+                _ => return Err(syn::Error::new(
+                    lit.span(),
+                    format!("Unknown database `{}`, expect sqlite, mysql or postgres.", value),
+                )),
+            });
+        }
+    }
+
+    if values.is_empty() {
+        #[cfg(feature = "sqlite")] values.push("sqlite");
+        #[cfg(feature = "mysql")] values.push("mysql");
+        #[cfg(feature = "postgres")] values.push("postgres");
+    }
+
+    Ok(values)
+}
+
+pub(crate) fn database_type(db_name: &str) -> TokenStream {
+    match db_name {
+        "sqlite" => quote!(cherry::sqlx::Sqlite),
+        "mysql" => quote!(cherry::sqlx::MySql),
+        _ => quote!(cherry::sqlx::Postgres),
+    }
+}
@@ -1,181 +1,817 @@
-use proc_macro::TokenStream;
-use std::collections::HashMap;
-use std::str::FromStr;
+use heck::ShoutySnakeCase;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Data, DeriveInput, Fields, GenericParam, Generics, Ident, LitStr, Member, Type, Visibility};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 
-use heck::SnakeCase;
-use proc_macro2::Span;
-use quote::quote;
-use syn::{Data, Ident, Lit, Meta, NestedMeta, punctuated::Punctuated};
+use crate::attr::{collect, database_type, databases, parse_cherry, rename, rename_rule, string, unknown};
+use crate::relation::{Kind, Relation, relation_impl};
 
-pub fn derive(ast: syn::DeriveInput) -> TokenStream {
-    let ident = &ast.ident;
-    let mut attrs = parse_attrs(&ast);
-    let table = attrs.remove("table").unwrap_or(ident.to_string().to_snake_case());
-    let db_values = attrs.remove("database").unwrap_or_default();
-    let mut tokens: Vec<String> = vec![];
-
-    for db_name in get_databases(&db_values) {
-        let token = quote!(
-            impl<'a> cherry::Cherry<'a, [db_type]> for #ident {
-                fn table() -> &'static str {
-                    #table
-                }
-                fn columns() -> Vec<(&'static str, &'static str)> {
-                    vec![ [fields] ]
-                }
+pub fn derive(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let container = Container::parse(&ast)?;
+    let fields = parse_fields(&ast, &container)?;
+    // the cached columns of `columns()` are shared by every instantiation
+    if let Some(f) = fields.iter().find(|f| f.flatten && uses_params(f.ty.to_token_stream(), &params(&ast.generics))) {
+        return Err(syn::Error::new(f.ty.span(), "Cherry can't flatten a field whose type uses a generic parameter."));
+    }
+    let mut tokens = TokenStream::new();
 
-                fn arguments(&'a self, arguments: &mut <[db_type] as cherry::sqlx::database::HasArguments<'a>>::Arguments) {
-                    use cherry::sqlx::Arguments;
-                    [arguments]
-                }
+    for db_name in &container.databases {
+        tokens.extend(cherry_impl(&ast, &container, &fields, db_name)?);
+        tokens.extend(primary_key_impl(&ast, &fields, db_name));
+        if *db_name == "postgres" && container.unnest {
+            tokens.extend(unnest_impl(&ast, &fields)?);
+        }
+    }
 
-                fn from_row(row: &<[db_type] as cherry::sqlx::Database>::Row) -> Result<Self, cherry::Error> {
-                    use cherry::sqlx::Row;
-                    Ok( Self { [from_row] } )
-                }
+    tokens.extend(column_consts(&ast, &fields));
+    if !container.relations.is_empty() {
+        tokens.extend(relation_impl(&ast, &fields, &container.relations)?);
+    }
+
+    if let Some(path) = &container.verify {
+        tokens.extend(crate::verify::verify(&ast, &container.table, &fields, path));
+    }
+
+    Ok(tokens)
+}
+
+pub(crate) struct Container {
+    pub(crate) table: String,
+    pub(crate) databases: Vec<&'static str>,
+    pub(crate) unnest: bool,
+    pub(crate) rename_all: Option<String>,
+    pub(crate) verify: Option<String>, // the schema file, `CHERRY_VERIFY` unless `verify = false`
+    pub(crate) soft_delete: Option<LitStr>, // the column set to the time of deletion
+    pub(crate) scopes: Vec<String>, // raw SQL conditions, unchecked, AND-ed with every select, update and delete
+    pub(crate) relations: Vec<Relation>,
+}
+
+impl Container {
+    const KEYS: &'static [&'static str] = &[
+        "table", "database", "unnest", "rename_all", "verify", "soft_delete", "scope", "has_many", "belongs_to",
+        "many_to_many",
+    ];
+
+    fn parse(ast: &DeriveInput) -> syn::Result<Self> {
+        let mut table = None;
+        let mut database = None;
+        let mut unnest = false;
+        let mut rename_all = None;
+        let mut verify = None;
+        let mut soft_delete = None;
+        let mut scopes = vec![];
+        let mut relations = vec![];
+
+        parse_cherry(&ast.attrs, |meta| {
+            match meta.path.get_ident().map(|ident| ident.to_string()).as_deref() {
+                Some("table") => table = Some(string(&meta)?.value()),
+                Some("database") => database = Some(string(&meta)?),
+                Some("unnest") => unnest = true,
+                Some("rename_all") => rename_all = Some(rename_rule(string(&meta)?)?),
+                Some("verify") => verify = Some(match meta.value()?.parse::<syn::Lit>()? {
+                    syn::Lit::Str(path) => Some(path.value()),
+                    syn::Lit::Bool(b) if !b.value => None,
+                    lit => return Err(syn::Error::new(lit.span(), "Expect the schema file, `verify = \"..\"`, or `verify = false`.")),
+                }),
+                Some("soft_delete") => soft_delete = Some(string(&meta)?),
+                // bound before the values of the query, a placeholder would shift them
+                Some("scope") => scopes.push(match string(&meta)? {
+                    sql if sql.value().contains('?') =>
+                        return Err(syn::Error::new(sql.span(), "Cherry binds no value to a `scope`, write the value in the SQL.")),
+                    sql => sql.value(),
+                }),
+                Some("has_many") => relations.push(Relation::parse(Kind::HasMany, &meta)?),
+                Some("belongs_to") => relations.push(Relation::parse(Kind::BelongsTo, &meta)?),
+                Some("many_to_many") => relations.push(Relation::parse(Kind::ManyToMany, &meta)?),
+                _ => return Err(unknown(&meta, Self::KEYS)),
             }
-        );
+            Ok(())
+        })?;
 
-        tokens.push(replace(token.to_string(), &db_name, &ast));
+        Ok(Self {
+            table: table.unwrap_or_else(|| rename(&ast.ident.unraw().to_string(), Some("snake_case"))),
+            databases: databases(database)?,
+            unnest,
+            rename_all,
+            verify: verify.unwrap_or_else(|| std::env::var("CHERRY_VERIFY").ok()),
+            soft_delete,
+            scopes,
+            relations,
+        })
     }
+}
 
-    TokenStream::from_str(tokens.join("\n").as_str())
-        .expect("Parse token stream failed")
+pub(crate) struct Field {
+    pub(crate) member: Member, // `self.name`, `self.0` of tuple structs
+    pub(crate) name: String, // `type` of `r#type`
+    pub(crate) column: String,
+    pub(crate) ty: Type,
+    pub(crate) vis: Visibility,
+    pub(crate) skip: bool, // not persisted, `Default::default()` when read
+    pub(crate) default: bool, // `Default::default()` when the column is missing from the row
+    pub(crate) primary_key: bool,
+    pub(crate) generated: bool, // filled by the database, left out of inserts
+    pub(crate) flatten: bool, // inline the columns of a field which derives Cherry too
+    pub(crate) prefix: String, // prefix of the flattened columns
+    pub(crate) version: bool, // optimistic locking, incremented by the updates of the instance
+    pub(crate) created_at: bool, // the current time, set by inserts
+    pub(crate) updated_at: bool, // the current time, set by inserts and updates
+    pub(crate) deleted_at: bool, // the `soft_delete` column of the container
+    pub(crate) tenant: bool, // filtered by the tenant of `TenantId::scope`
 }
 
-fn replace(token: String, db_name: &str, ast: &syn::DeriveInput) -> String {
-    let fields = match &ast.data {
-        Data::Struct(ref s) => &s.fields,
-        _ => panic!("Cherry only allow impl for struct."),
-    };
+impl Field {
+    const KEYS: &'static [&'static str] = &[
+        "column", "skip", "default", "primary_key", "auto_increment", "generated", "db_default", "flatten",
+        "prefix", "version", "created_at", "updated_at", "tenant",
+    ];
+
+    fn parse(index: usize, field: &syn::Field, container: &Container) -> syn::Result<Self> {
+        let mut column = None;
+        let mut skip = false;
+        let mut default = false;
+        let mut primary_key = false;
+        let mut generated = false;
+        let mut flatten = false;
+        let mut prefix: Option<LitStr> = None;
+        let mut version = false;
+        let mut created_at = false;
+        let mut updated_at = false;
+        let mut tenant = false;
+
+        parse_cherry(&field.attrs, |meta| {
+            match meta.path.get_ident().map(|ident| ident.to_string()).as_deref() {
+                Some("column") => column = Some(string(&meta)?.value()),
+                Some("skip") => skip = true,
+                Some("default") => default = true,
+                Some("primary_key") => primary_key = true,
+                Some("auto_increment" | "generated" | "db_default") => generated = true,
+                Some("flatten") => flatten = true,
+                Some("prefix") => prefix = Some(string(&meta)?),
+                Some("version") => version = true,
+                Some("created_at") => created_at = true,
+                Some("updated_at") => updated_at = true,
+                Some("tenant") => tenant = true,
+                _ => return Err(unknown(&meta, Self::KEYS)),
+            }
+            Ok(())
+        })?;
 
-    let fields_str = fields.iter().filter_map(|field|
-        field.ident.as_ref().map(|ident| ident.to_string())
-    ).collect::<Vec<String>>();
-
-    let fields = fields_str.iter().map(|s|
-        format!(r#" ("{0}", "{0}"), "#, s)
-    ).collect::<String>();
-
-    let arguments = fields_str.iter().map(|s|
-        format!(r#" arguments.add(&self.{}); "#, s)
-    ).collect::<String>();
-
-    let from_row = fields_str.iter().map(|s|
-        format!(r#" {0}: row.try_get("{0}")?, "#, s)
-    ).collect::<String>();
-
-    token
-        .replace("[db_type]", database_type(db_name))
-        .replace("[fields]", fields.as_str())
-        .replace("[arguments]", arguments.as_str())
-        .replace("[from_row]", from_row.as_str())
-}
-
-#[allow(unused_mut)]
-fn get_databases(prop: &str) -> Vec<String> {
-    let mut values = prop.split(',')
-        .filter(|v| !v.trim().is_empty())
-        .map(|v| v.trim().to_string())
-        .collect::<Vec<String>>();
-
-    if values.is_empty() {
-        #[cfg(feature = "sqlite")] values.push("sqlite".into());
-        #[cfg(feature = "mysql")] values.push("mysql".into());
-        #[cfg(feature = "postgres")] values.push("postgres".into());
-        #[cfg(feature = "mssql")] values.push("mssql".into());
-    }
-
-    values
-}
-
-fn database_type(db_name: &str) -> &'static str {
-    match db_name {
-        "sqlite" => "cherry::sqlx::Sqlite",
-        "mysql" => "cherry::sqlx::MySql",
-        "postgres" => "cherry::sqlx::Postgres",
-        _ => panic!("Unknown database `{}`", db_name),
-    }
-}
-
-fn parse_attrs(ast: &syn::DeriveInput) -> HashMap<String, String> {
-    ast.attrs.iter().find_map(|attr| {
-        match attr.parse_meta().unwrap() {
-            Meta::List(meta_list) => Some(meta_list),
-            _ => None
-        }
-    }).filter(|meta_list| {
-        meta_list.path.get_ident() == Some(&Ident::new("cherry", Span::call_site()))
-    }).map(|meta_list| {
-        // extract_attrs(&meta_list.nested)
-        props(&meta_list.nested)
-    }).unwrap_or_default()
-
-    // match value {
-    //     Some(Some(table)) => table,
-    //     Some(_) => panic!("Unknown attribute."),
-    //     // The `cherry` attribute not exists. Pick the default name.
-    //     _ => ast.ident.to_string().to_snake_case(),
-    // }
-}
-
-fn props<P>(props: &Punctuated<NestedMeta, P>) -> HashMap<String, String> {
-    props.iter().filter_map(|v| match v {
-        NestedMeta::Meta(Meta::NameValue(value)) => Some(value),
-        _ => None
-    }).map(|v| {
-        let key = v.path.get_ident().map(|ident| ident.to_string());
-        let value = match &v.lit {
-            Lit::Str(s) => Some(s.value()),
-            _ => None,
+        let (member, name) = match &field.ident {
+            Some(ident) => (Member::Named(ident.clone()), ident.unraw().to_string()),
+            None => (Member::Unnamed(index.into()), index.to_string()),
         };
-        (key, value)
-    }).filter_map(|(k, v)|
-        match k.is_some() && v.is_some() {
-            true => Some((k.unwrap(), v.unwrap())),
-            _ => None,
+        let column = match (column, &field.ident) {
+            (Some(column), _) => column,
+            (None, Some(_)) => rename(&name, container.rename_all.as_deref()),
+            (None, None) if skip || flatten => name.clone(),
+            (None, None) => return Err(syn::Error::new(
+                field.span(),
+                "Fields of tuple structs need a column name, `#[cherry(column = \"..\")]`.",
+            )),
+        };
+
+        // the field name, `field.span()` is only its first token on stable
+        let span = field.ident.as_ref().map_or_else(|| field.ty.span(), |ident| ident.span());
+        if skip && primary_key {
+            return Err(syn::Error::new(span, format!("Primary key field `{}` can't be skipped.", name)));
+        }
+        if version && (skip || primary_key || flatten) {
+            return Err(syn::Error::new(span, "The `version` field can't be skipped, flattened or a key."));
+        }
+        if (created_at || updated_at) && (skip || primary_key || generated || flatten || version) {
+            return Err(syn::Error::new(
+                span,
+                "Timestamp fields can't be skipped, flattened, generated, a key or the `version`.",
+            ));
+        }
+        let deleted_at = container.soft_delete.as_ref().is_some_and(|lit| lit.value() == column);
+        if deleted_at && (skip || primary_key || generated || flatten || version || created_at || updated_at) {
+            return Err(syn::Error::new(
+                span,
+                "The `soft_delete` field can't be skipped, flattened, generated, a key, the `version` or a timestamp.",
+            ));
+        }
+        if tenant && (skip || generated || flatten || version || created_at || updated_at || deleted_at) {
+            return Err(syn::Error::new(
+                span,
+                "The `tenant` field can't be skipped, flattened, generated, the `version` or a timestamp.",
+            ));
+        }
+        if created_at && updated_at {
+            return Err(syn::Error::new(span, "A field can't be both `created_at` and `updated_at`."));
         }
-    ).collect()
+        if let Some(prefix) = prefix.as_ref().filter(|_| !flatten) {
+            return Err(syn::Error::new(prefix.span(), "`prefix` only applies to `flatten` fields."));
+        }
+
+        Ok(Self {
+            member,
+            name,
+            column,
+            ty: field.ty.clone(),
+            vis: field.vis.clone(),
+            skip,
+            default,
+            primary_key,
+            generated,
+            flatten,
+            prefix: prefix.map(|p| p.value()).unwrap_or_default(),
+            version,
+            created_at,
+            updated_at,
+            deleted_at,
+            tenant,
+        })
+    }
 }
 
+pub(crate) fn parse_fields(ast: &DeriveInput, container: &Container) -> syn::Result<Vec<Field>> {
+    let fields = match &ast.data {
+        Data::Struct(s) => &s.fields,
+        Data::Enum(e) => return Err(syn::Error::new(
+            e.enum_token.span(),
+            "Cherry can only be derived for structs, derive `CherryEnum` for enums stored in a column.",
+        )),
+        Data::Union(u) => return Err(syn::Error::new(u.union_token.span(), "Cherry can only be derived for structs.")),
+    };
+    if let Fields::Unit = fields {
+        return Err(syn::Error::new(ast.ident.span(), "Cherry can't be derived for unit structs, there are no columns."));
+    }
 
-/*
-fn extract_attrs<P>(props: &Punctuated<NestedMeta, P>) -> Option<String> {
-    if props.len() > 1 {
-        return None;
+    let fields = collect(fields.iter().enumerate().map(|(index, field)| Field::parse(index, field, container)))?;
+    if let Some(f) = fields.iter().filter(|f| f.version).nth(1) {
+        return Err(syn::Error::new(f.member.span(), "Only one field can be the `version`."));
     }
-    let lit = match props.first() {
-        Some(NestedMeta::Meta(Meta::NameValue(value))) => Some(value),
-        _ => None
-    }.filter(|value| {
-        value.path.get_ident() == Some(&Ident::new("table", Span::call_site()))
-    }).map(|value| &value.lit);
-    match lit {
-        Some(Lit::Str(s)) => Some(s.value()),
-        _ => None
+    if let Some(f) = fields.iter().filter(|f| f.created_at).nth(1) {
+        return Err(syn::Error::new(f.member.span(), "Only one field can be `created_at`."));
     }
+    if let Some(f) = fields.iter().filter(|f| f.updated_at).nth(1) {
+        return Err(syn::Error::new(f.member.span(), "Only one field can be `updated_at`."));
+    }
+    if let Some(f) = fields.iter().filter(|f| f.tenant).nth(1) {
+        return Err(syn::Error::new(f.member.span(), "Only one field can be the `tenant`."));
+    }
+    if let Some(lit) = container.soft_delete.as_ref().filter(|_| !fields.iter().any(|f| f.deleted_at)) {
+        return Err(syn::Error::new(lit.span(), format!("No field has the `soft_delete` column `{}`.", lit.value())));
+    }
+    Ok(fields)
 }
-*/
 
-/*
-fn extract_attrs<P>(props: &Punctuated<NestedMeta, P>) -> (Option<String>, Option<String>) {
-    let db_name = match props.first() {
-        Some(NestedMeta::Meta(Meta::Path(p))) => Some(p.to_token_stream().to_string()),
-        _ => None
+fn cherry_impl(ast: &DeriveInput, container: &Container, fields: &[Field], db_name: &str) -> syn::Result<TokenStream> {
+    let ident = &ast.ident;
+    let table = &container.table;
+    let db = database_type(db_name);
+    let generics = cherry_generics(ast, fields, &db);
+    let impl_generics = impl_generics(&generics);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let columns = columns_body(fields, &db, quote!(columns), |f| !f.skip);
+    let insert_columns = columns_body(fields, &db, quote!(insert_columns), |f| !f.skip && !f.generated);
+    let insert_values = insert_values_body(fields, &db);
+    let update_columns = columns_body(fields, &db, quote!(update_columns), updated);
+    let update_arguments = arguments_body(fields, &db, quote!(update_arguments), updated);
+
+    let column_arguments = fields.iter().filter(|f| !f.skip && !f.flatten).map(|f| {
+        let (member, column) = (&f.member, &f.column);
+        quote!(#column => arguments.add(&self.#member),)
+    });
+
+    let optional_column = |f: Option<&Field>| match f {
+        Some(f) => {
+            let column = &f.column;
+            quote!(Some(#column))
+        }
+        _ => quote!(None),
     };
+    let version = optional_column(fields.iter().find(|f| f.version));
+    let soft_delete = optional_column(fields.iter().find(|f| f.deleted_at));
+    let soft_delete_arguments = fields.iter().filter(|f| f.deleted_at).map(|f| {
+        let ty = &f.ty;
+        quote!(arguments.add(<#ty as cherry::Timestamp>::now());)
+    });
+    let scopes = &container.scopes;
+    let tenant = optional_column(fields.iter().find(|f| f.tenant));
+    let tenant_arguments = match fields.iter().find(|f| f.tenant) {
+        Some(f) => {
+            let ty = &f.ty;
+            quote!(
+                match cherry::TenantId::<#ty>::current() {
+                    Some(tenant) => arguments.add(tenant),
+                    _ => return false,
+                }
+            )
+        }
+        _ => quote!(),
+    };
+    // the row is written with its own tenant, it has to be the one of the scope
+    let tenant_check = fields.iter().find(|f| f.tenant).map(|f| {
+        let (member, ty) = (&f.member, &f.ty);
+        quote!(
+            fn tenant_check(&'cherry self) -> cherry::TenantCheck<'cherry> {
+                let tenant = &self.#member;
+                Box::new(move || match cherry::TenantId::<#ty>::current() {
+                    Some(current) => current == *tenant,
+                    _ => true,
+                })
+            }
+        )
+    });
+    let created_at = optional_column(fields.iter().find(|f| f.created_at));
+    let updated_at = optional_column(fields.iter().find(|f| f.updated_at));
+    let touch_arguments = fields.iter().filter(|f| f.updated_at).map(|f| {
+        let ty = &f.ty;
+        quote!(arguments.add(<#ty as cherry::Timestamp>::now());)
+    });
+
+    let primary_key = fields.iter().filter(|f| f.primary_key).map(|f| &f.column);
+    let primary_key_arguments = fields.iter().filter(|f| f.primary_key).map(|f| {
+        let member = &f.member;
+        quote!(arguments.add(&self.#member);)
+    });
+
+    let from_row = fields.iter().map(|f| {
+        let column = &f.column;
+        let prefix = &f.prefix;
+        from_row_field(f, &db, quote!(#column), quote!(#prefix))
+    });
+    let from_row_prefixed = fields.iter().map(|f| {
+        let column = &f.column;
+        let prefix = &f.prefix;
+        from_row_field(
+            f, &db,
+            quote!(format!("{}{}", prefix, #column).as_str()),
+            quote!(format!("{}{}", prefix, #prefix).as_str()),
+        )
+    });
+
+    Ok(quote!(
+        impl #impl_generics cherry::Cherry<'cherry, #db> for #ident #ty_generics #where_clause {
+            fn table() -> &'static str {
+                #table
+            }
+
+            fn columns() -> Vec<(&'static str, &'static str)> {
+                #columns
+            }
 
-    let table = props.iter().find_map(|item| {
-        if let NestedMeta::Meta(Meta::NameValue(name_value)) = item {
-            if name_value.path.get_ident() ==
-                Some(&Ident::new("table", Span::call_site())){
-                if let Lit::Str(s) = &name_value.lit {
-                    return Some(s.value());
+            fn insert_columns() -> Vec<(&'static str, &'static str)> {
+                #insert_columns
+            }
+
+            fn insert_values(&'cherry self) -> cherry::InsertValues<'cherry, #db> {
+                #insert_values
+            }
+
+            fn update_columns() -> Vec<(&'static str, &'static str)> {
+                #update_columns
+            }
+
+            fn update_arguments(&'cherry self, arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments) {
+                use cherry::sqlx::Arguments;
+                #update_arguments
+            }
+
+            fn column_arguments(&'cherry self, column: &str, arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments) -> bool {
+                use cherry::sqlx::Arguments;
+                match column {
+                    #(#column_arguments)*
+                    _ => return false,
                 }
+                true
+            }
+
+            fn version() -> Option<&'static str> {
+                #version
+            }
+
+            fn created_at() -> Option<&'static str> {
+                #created_at
+            }
+
+            fn updated_at() -> Option<&'static str> {
+                #updated_at
+            }
+
+            fn touch_arguments(arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments) {
+                use cherry::sqlx::Arguments;
+                #(#touch_arguments)*
+            }
+
+            fn soft_delete() -> Option<&'static str> {
+                #soft_delete
+            }
+
+            fn soft_delete_arguments(arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments) {
+                use cherry::sqlx::Arguments;
+                #(#soft_delete_arguments)*
+            }
+
+            fn scopes() -> &'static [&'static str] {
+                &[ #(#scopes),* ]
+            }
+
+            fn tenant() -> Option<&'static str> {
+                #tenant
+            }
+
+            fn tenant_arguments(arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments) -> bool {
+                use cherry::sqlx::Arguments;
+                #tenant_arguments
+                true
+            }
+
+            #tenant_check
+
+            fn primary_key() -> &'static [&'static str] {
+                &[ #(#primary_key),* ]
+            }
+
+            fn primary_key_arguments(&'cherry self, arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments) {
+                use cherry::sqlx::Arguments;
+                #(#primary_key_arguments)*
+            }
+
+            fn from_row(row: &<#db as cherry::sqlx::Database>::Row) -> Result<Self, cherry::Error> {
+                use cherry::sqlx::Row;
+                Ok(Self { #(#from_row),* })
+            }
+
+            fn from_row_prefixed(row: &<#db as cherry::sqlx::Database>::Row, prefix: &str) -> Result<Self, cherry::Error> {
+                use cherry::sqlx::Row;
+                Ok(Self { #(#from_row_prefixed),* })
             }
         }
-        None
+    ))
+}
+
+// The fields set by the updates of an instance. `updated_at` is set by every update query,
+// `Cherry::touch_arguments()`.
+fn updated(f: &&Field) -> bool {
+    !f.skip && !f.generated && !f.primary_key && !f.version && !f.created_at && !f.updated_at && !f.deleted_at
+        && !f.tenant
+}
+
+// `find()` by the key fields, only for entities declaring a primary key.
+fn primary_key_impl(ast: &DeriveInput, fields: &[Field], db_name: &str) -> TokenStream {
+    let keys = fields.iter().filter(|f| f.primary_key).collect::<Vec<&Field>>();
+    if keys.is_empty() {
+        return TokenStream::new();
+    }
+
+    let ident = &ast.ident;
+    let db = database_type(db_name);
+    // the key is cloned by `key()`, the version bound by value
+    let generics = generics(ast, fields, |ty, field| match field.primary_key || field.version {
+        true => {
+            let bound = cherry_bound(ty, field, &db);
+            quote!(#bound + Clone)
+        }
+        _ => cherry_bound(ty, field, &db),
+    });
+    let impl_generics = impl_generics(&generics);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (key, arguments, value) = match keys.as_slice() {
+        [f] => {
+            let (ty, member) = (&f.ty, &f.member);
+            (quote!(#ty), quote!(arguments.add(key);), quote!(self.#member.clone()))
+        }
+        _ => {
+            let types = keys.iter().map(|f| &f.ty);
+            let indexes = (0..keys.len()).map(syn::Index::from);
+            let members = keys.iter().map(|f| &f.member);
+            (
+                quote!((#(#types),*)),
+                quote!(#(arguments.add(key.#indexes);)*),
+                quote!((#(self.#members.clone()),*)),
+            )
+        }
+    };
+
+    let update_by_key_arguments = update_by_key_arguments(fields, &db);
+
+    // referenced by a single column
+    let single_key = match keys.len() {
+        1 => quote!(impl #impl_generics cherry::SingleKey<'cherry, #db> for #ident #ty_generics #where_clause {}),
+        _ => quote!(),
+    };
+
+    quote!(
+        impl #impl_generics cherry::PrimaryKey<'cherry, #db> for #ident #ty_generics #where_clause {
+            type Key = #key;
+
+            fn key_arguments(key: Self::Key, arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments) {
+                use cherry::sqlx::Arguments;
+                #arguments
+            }
+
+            fn key(&self) -> Self::Key {
+                #value
+            }
+
+            #update_by_key_arguments
+        }
+
+        #single_key
+    )
+}
+
+// `update_by_key_arguments()` of a versioned entity: the fields are borrowed apart, the version
+// mutably to be incremented once updated, and bound by value.
+fn update_by_key_arguments(fields: &[Field], db: &TokenStream) -> TokenStream {
+    let version = match fields.iter().find(|f| f.version) {
+        Some(f) => f,
+        _ => return TokenStream::new(),
+    };
+    let bound = fields.iter()
+        .filter(|f| updated(f) || f.primary_key)
+        .map(|f| (f, format_ident!("f{}", f.name)))
+        .collect::<Vec<(&Field, Ident)>>();
+    let borrows = bound.iter().map(|(f, v)| {
+        let member = &f.member;
+        quote!(#member: #v)
+    });
+    let shared = bound.iter().map(|(f, v)| {
+        let ty = &f.ty;
+        quote!(let #v: &'cherry #ty = #v;)
+    });
+    let columns = bound.iter().filter(|(f, _)| updated(f) && !f.flatten).map(|(f, v)| {
+        let column = &f.column;
+        quote!(#column => arguments.add(#v),)
+    });
+    let all = bound.iter().filter(|(f, _)| updated(f)).map(|(f, v)| {
+        let ty = &f.ty;
+        match f.flatten {
+            true => quote!(<#ty as cherry::Cherry<'cherry, #db>>::update_arguments(#v, arguments);),
+            _ => quote!(arguments.add(#v);),
+        }
+    });
+    let keys = bound.iter().filter(|(f, _)| f.primary_key).map(|(_, v)| quote!(arguments.add(#v);));
+    let version_member = &version.member;
+
+    quote!(
+        fn update_by_key_arguments(&'cherry mut self, columns: Option<&[&str]>,
+                                   arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments)
+                                   -> Option<Box<dyn FnOnce() + Send + 'cherry>> {
+            use cherry::sqlx::Arguments;
+            let Self { #(#borrows,)* #version_member: version, .. } = self;
+            #(#shared)*
+            match columns {
+                Some(columns) => for column in columns {
+                    match *column {
+                        #(#columns)*
+                        _ => {}
+                    }
+                },
+                _ => {
+                    #(#all)*
+                }
+            }
+            #(#keys)*
+            arguments.add(Clone::clone(&*version));
+            Some(Box::new(move || *version += 1))
+        }
+    )
+}
+
+// The body of `columns()` or `insert_columns()`. The columns of flattened fields are known at
+// runtime only, prefixed names are leaked once to be `&'static str`.
+fn columns_body<F>(fields: &[Field], db: &TokenStream, method: TokenStream, filter: F) -> TokenStream
+    where F: Fn(&&Field) -> bool {
+    let fields = fields.iter().filter(filter).collect::<Vec<&Field>>();
+    if !fields.iter().any(|f| f.flatten) {
+        let columns = fields.iter().map(|f| {
+            let (name, column) = (&f.name, &f.column);
+            quote!((#name, #column))
+        });
+        return quote!(vec![ #(#columns),* ]);
+    }
+
+    let columns = fields.iter().map(|f| {
+        let (name, column, ty, prefix) = (&f.name, &f.column, &f.ty, &f.prefix);
+        match f.flatten {
+            true => quote!(columns.extend(cherry::prefix_columns(<#ty as cherry::Cherry<'cherry, #db>>::#method(), #prefix));),
+            _ => quote!(columns.push((#name, #column));),
+        }
+    });
+
+    quote!(
+        static COLUMNS: std::sync::OnceLock<Vec<(&'static str, &'static str)>> = std::sync::OnceLock::new();
+        COLUMNS.get_or_init(|| {
+            let mut columns = vec![];
+            #(#columns)*
+            columns
+        }).clone()
+    )
+}
+
+fn arguments_body<F>(fields: &[Field], db: &TokenStream, method: TokenStream, filter: F) -> TokenStream
+    where F: Fn(&&Field) -> bool {
+    let arguments = fields.iter().filter(filter).map(|f| {
+        let (member, ty) = (&f.member, &f.ty);
+        match f.flatten {
+            true => quote!(<#ty as cherry::Cherry<'cherry, #db>>::#method(&self.#member, arguments);),
+            _ => quote!(arguments.add(&self.#member);),
+        }
+    });
+    quote!(#(#arguments)*)
+}
+
+// The body of `insert_values()`: the fields are borrowed now, bound later with the current time
+// for the timestamp fields instead of their value.
+fn insert_values_body(fields: &[Field], db: &TokenStream) -> TokenStream {
+    let fields = fields.iter().filter(|f| !f.skip && !f.generated).collect::<Vec<&Field>>();
+    let values = (0..fields.len()).map(|i| format_ident!("v{}", i)).collect::<Vec<Ident>>();
+    let borrows = fields.iter().zip(&values).map(|(f, v)| {
+        let (member, ty) = (&f.member, &f.ty);
+        match f.flatten {
+            true => quote!(let #v = <#ty as cherry::Cherry<'cherry, #db>>::insert_values(&self.#member);),
+            _ if f.created_at || f.updated_at => quote!(),
+            _ => quote!(let #v = &self.#member;),
+        }
     });
+    let arguments = fields.iter().zip(&values).map(|(f, v)| {
+        let ty = &f.ty;
+        match f.flatten {
+            true => quote!(#v(arguments);),
+            _ if f.created_at || f.updated_at => quote!(arguments.add(<#ty as cherry::Timestamp>::now());),
+            _ => quote!(arguments.add(#v);),
+        }
+    });
+    quote!(
+        #(#borrows)*
+        Box::new(move |arguments: &mut <#db as cherry::sqlx::database::HasArguments<'cherry>>::Arguments| {
+            use cherry::sqlx::Arguments;
+            #(#arguments)*
+        })
+    )
+}
+
+fn from_row_field(f: &Field, db: &TokenStream, column: TokenStream, prefix: TokenStream) -> TokenStream {
+    let (member, ty) = (&f.member, &f.ty);
+    match (f.skip, f.default, f.flatten) {
+        (true, _, _) => quote!(#member: Default::default()),
+        (_, _, true) => quote!(#member: <#ty as cherry::Cherry<'cherry, #db>>::from_row_prefixed(row, #prefix)?),
+        (_, true, _) => quote!(
+            #member: match row.try_get(#column) {
+                Err(cherry::sqlx::Error::ColumnNotFound(_)) => Default::default(),
+                v => v?,
+            }
+        ),
+        _ => quote!(#member: row.try_get(#column)?),
+    }
+}
+
+fn unnest_impl(ast: &DeriveInput, fields: &[Field]) -> syn::Result<TokenStream> {
+    let ident = &ast.ident;
+    let fields = fields.iter().filter(|f| !f.skip && !f.generated).collect::<Vec<&Field>>();
+    if let Some(f) = fields.iter().find(|f| f.flatten) {
+        return Err(syn::Error::new(f.member.span(), "Cherry can't insert flattened fields with `unnest`."));
+    }
+
+    let db = quote!(cherry::sqlx::Postgres);
+    let generics = generics(ast, fields.iter().copied(), |ty, _| quote!(
+        #ty: cherry::sqlx::postgres::PgHasArrayType + cherry::sqlx::Encode<'cherry, #db> + cherry::sqlx::Type<#db> + Sync
+    ));
+    let impl_generics = impl_generics(&generics);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let types = fields.iter().map(|f| {
+        let ty = &f.ty;
+        quote!(cherry::postgres::UnnestArray::<#ty>::type_name())
+    });
+    // the columns are borrowed now, bound later like `insert_values()`
+    let values = (0..fields.len()).map(|i| format_ident!("v{}", i)).collect::<Vec<Ident>>();
+    let borrows = fields.iter().zip(&values).map(|(f, v)| {
+        let (member, ty) = (&f.member, &f.ty);
+        quote!(let #v = v.iter().map(|row| &row.#member).collect::<Vec<&#ty>>();)
+    });
+    let arguments = fields.iter().zip(&values).map(|(f, v)| {
+        let ty = &f.ty;
+        match f.created_at || f.updated_at {
+            true => quote!(arguments.add(#v.iter().map(|_| <#ty as cherry::Timestamp>::now()).collect::<Vec<#ty>>());),
+            _ => quote!(arguments.add(cherry::postgres::UnnestArray(#v));),
+        }
+    });
+
+    Ok(quote!(
+        impl #impl_generics cherry::postgres::Unnest<'cherry> for #ident #ty_generics #where_clause {
+            fn array_types() -> Vec<String> {
+                vec![ #(#types),* ]
+            }
+
+            fn array_values(v: &'cherry [Self]) -> cherry::InsertValues<'cherry, #db> {
+                #(#borrows)*
+                Box::new(move |arguments: &mut cherry::sqlx::postgres::PgArguments| {
+                    use cherry::sqlx::Arguments;
+                    #(#arguments)*
+                })
+            }
+        }
+    ))
+}
+
+// Typed columns, `User::NAME: cherry::Column<User, String>`, the primary key ones marked with
+// `cherry::KeyColumn`, the ones not set by the updates of an instance with `cherry::ManagedColumn`.
+fn column_consts(ast: &DeriveInput, fields: &[Field]) -> TokenStream {
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let consts = fields.iter().filter(|f| !f.skip && !f.flatten).map(|f| {
+        let (vis, ty, column) = (&f.vis, &f.ty, &f.column);
+        let name = match &f.member {
+            Member::Named(_) => &f.name,
+            Member::Unnamed(_) => &f.column,
+        };
+        let name = format_ident!("{}", name.to_shouty_snake_case(), span = f.member.span());
+        let member = &f.member;
+        let kind = match f.primary_key {
+            true => quote!(cherry::KeyColumn),
+            _ if !updated(&f) => quote!(cherry::ManagedColumn),
+            _ => quote!(()),
+        };
+        quote!(#vis const #name: cherry::Column<Self, #ty, #kind> = cherry::Column::new(#column, |t| &mut t.#member);)
+    });
+    // the columns checked by the `verify` of a struct flattening this one
+    let columns = fields.iter().filter(|f| !f.skip).map(|f| {
+        let (ty, column, prefix) = (&f.ty, &f.column, &f.prefix);
+        match f.flatten {
+            true => quote!(cherry::verify::Column::Flatten(#prefix, <#ty>::__CHERRY_COLUMNS)),
+            _ => quote!(cherry::verify::Column::Name(#column)),
+        }
+    });
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#consts)*
+
+            #[doc(hidden)]
+            pub const __CHERRY_COLUMNS: &'static [cherry::verify::Column] = &[ #(#columns),* ];
+        }
+    )
+}
+
+// The bounds for the fields used by the `Cherry` impl.
+fn cherry_generics(ast: &DeriveInput, fields: &[Field], db: &TokenStream) -> Generics {
+    generics(ast, fields, |ty, field| cherry_bound(ty, field, db))
+}
+
+fn cherry_bound(ty: &Type, field: &Field, db: &TokenStream) -> TokenStream {
+    match (field.skip, field.flatten) {
+        (true, _) => quote!(#ty: Default),
+        (_, true) => quote!(#ty: cherry::Cherry<'cherry, #db>),
+        _ => quote!(#ty: for<'r> cherry::sqlx::Decode<'r, #db> + cherry::sqlx::Encode<'cherry, #db> + cherry::sqlx::Type<#db> + Send + Sync),
+    }
+}
+
+// The generics of the struct, plus `Send + Unpin` on the type parameters as required by
+// `Cherry`, and the bounds given by `bound` for the fields whose type uses a parameter.
+fn generics<'f, I, F>(ast: &DeriveInput, fields: I, bound: F) -> Generics
+    where
+        I: IntoIterator<Item = &'f Field>,
+        F: Fn(&Type, &Field) -> TokenStream {
+    let mut generics = ast.generics.clone();
+    let params = params(&generics);
+    if params.is_empty() {
+        return generics;
+    }
+
+    let mut predicates = generics.type_params().map(|t| {
+        let ident = &t.ident;
+        quote!(#ident: Send + Unpin)
+    }).collect::<Vec<TokenStream>>();
+    predicates.extend(fields.into_iter()
+        .filter(|f| uses_params(f.ty.to_token_stream(), &params))
+        .map(|f| bound(&f.ty, f)));
+    let where_clause = generics.make_where_clause();
+    for predicate in predicates {
+        where_clause.predicates.push(syn::parse2(predicate).expect("Parse where predicate failed"));
+    }
+    generics
+}
+
+// The impl generics with the lifetime of the `Cherry` trait first.
+fn impl_generics(generics: &Generics) -> TokenStream {
+    let mut generics = generics.clone();
+    generics.params.insert(0, syn::parse_quote!('cherry));
+    generics.split_for_impl().0.to_token_stream()
+}
+
+pub(crate) fn params(generics: &Generics) -> Vec<String> {
+    generics.params.iter().map(|param| match param {
+        GenericParam::Type(t) => t.ident.to_string(),
+        GenericParam::Lifetime(l) => l.lifetime.ident.to_string(),
+        GenericParam::Const(c) => c.ident.to_string(),
+    }).collect()
+}
 
-    (db_name, table)
+pub(crate) fn uses_params(tokens: TokenStream, params: &[String]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => params.contains(&ident.to_string()),
+        TokenTree::Group(group) => uses_params(group.stream(), params),
+        _ => false,
+    })
 }
-*/
\ No newline at end of file
@@ -0,0 +1,165 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+use syn::spanned::Spanned;
+
+use crate::attr::{collect, database_type, databases, parse_cherry, rename, rename_rule, string, unknown};
+
+type ReprImpl = fn(&DeriveInput, TokenStream, &[(syn::Ident, String)]) -> TokenStream;
+
+pub fn derive(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let mut database = None;
+    let mut repr: Option<LitStr> = None;
+    let mut rename_all = None;
+    parse_cherry(&ast.attrs, |meta| {
+        match meta.path.get_ident().map(|ident| ident.to_string()).as_deref() {
+            Some("database") => database = Some(string(&meta)?),
+            Some("repr") => repr = Some(string(&meta)?),
+            Some("rename_all") => rename_all = Some(rename_rule(string(&meta)?)?),
+            _ => return Err(unknown(&meta, &["database", "repr", "rename_all"])),
+        }
+        Ok(())
+    })?;
+
+    let variants = match &ast.data {
+        Data::Enum(e) => &e.variants,
+        Data::Struct(s) => return Err(syn::Error::new(s.struct_token.span(), "CherryEnum can only be derived for enums.")),
+        Data::Union(u) => return Err(syn::Error::new(u.union_token.span(), "CherryEnum can only be derived for enums.")),
+    };
+
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new(ast.generics.span(), "CherryEnum can't be derived for generic enums."));
+    }
+
+    let variants = collect(variants.iter().map(|variant| {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.fields.span(),
+                format!("CherryEnum only allow unit variants, `{}` has fields.", variant.ident),
+            ));
+        }
+        let mut value = None;
+        parse_cherry(&variant.attrs, |meta| {
+            if !meta.path.is_ident("rename") {
+                return Err(unknown(&meta, &["rename"]));
+            }
+            value = Some(string(&meta)?.value());
+            Ok(())
+        })?;
+        let value = value.unwrap_or_else(|| rename(&variant.ident.to_string(), rename_all.as_deref()));
+        Ok((variant.ident.clone(), value))
+    }))?;
+
+    // the impls of the representation, and the type it's stored as
+    let (repr_impl, repr_ty): (ReprImpl, TokenStream) = match &repr {
+        None => (text_impl, quote!(String)),
+        Some(lit) if lit.value() == "text" => (text_impl, quote!(String)),
+        Some(lit) if lit.value() == "i32" => (i32_impl, quote!(i32)),
+        Some(lit) => return Err(syn::Error::new(
+            lit.span(),
+            format!("Unknown repr `{}`, expect `text` or `i32`.", lit.value()),
+        )),
+    };
+
+    Ok(databases(database)?.iter()
+        .map(|db_name| match *db_name {
+            "postgres" => {
+                let array_impl = array_impl(&ast, repr_ty.clone());
+                let repr_impl = repr_impl(&ast, database_type(db_name), &variants);
+                quote!(#repr_impl #array_impl)
+            }
+            _ => repr_impl(&ast, database_type(db_name), &variants),
+        })
+        .collect())
+}
+
+// Postgres: bound as an array like its representation, in `unnest` inserts and `= ANY($1)`.
+fn array_impl(ast: &DeriveInput, repr_ty: TokenStream) -> TokenStream {
+    let ident = &ast.ident;
+    quote!(
+        impl cherry::sqlx::postgres::PgHasArrayType for #ident {
+            fn array_type_info() -> cherry::sqlx::postgres::PgTypeInfo {
+                <#repr_ty as cherry::sqlx::postgres::PgHasArrayType>::array_type_info()
+            }
+
+            fn array_compatible(ty: &cherry::sqlx::postgres::PgTypeInfo) -> bool {
+                <#repr_ty as cherry::sqlx::postgres::PgHasArrayType>::array_compatible(ty)
+            }
+        }
+    )
+}
+
+fn text_impl(ast: &DeriveInput, db: TokenStream, variants: &[(syn::Ident, String)]) -> TokenStream {
+    let ident = &ast.ident;
+    let encode = variants.iter().map(|(variant, value)| quote!(Self::#variant => #value,));
+    let decode = variants.iter().map(|(variant, value)| quote!(#value => Ok(Self::#variant),));
+
+    quote!(
+        impl cherry::sqlx::Type<#db> for #ident {
+            fn type_info() -> <#db as cherry::sqlx::Database>::TypeInfo {
+                <str as cherry::sqlx::Type<#db>>::type_info()
+            }
+
+            fn compatible(ty: &<#db as cherry::sqlx::Database>::TypeInfo) -> bool {
+                <str as cherry::sqlx::Type<#db>>::compatible(ty)
+            }
+        }
+
+        impl<'q> cherry::sqlx::Encode<'q, #db> for #ident {
+            fn encode_by_ref(&self, buf: &mut <#db as cherry::sqlx::database::HasArguments<'q>>::ArgumentBuffer)
+                -> cherry::sqlx::encode::IsNull {
+                let value: &'static str = match self { #(#encode)* };
+                <&str as cherry::sqlx::Encode<'q, #db>>::encode(value, buf)
+            }
+        }
+
+        impl<'r> cherry::sqlx::Decode<'r, #db> for #ident {
+            fn decode(value: <#db as cherry::sqlx::database::HasValueRef<'r>>::ValueRef)
+                -> Result<Self, cherry::sqlx::error::BoxDynError> {
+                let value = <&str as cherry::sqlx::Decode<'r, #db>>::decode(value)?;
+                match value {
+                    #(#decode)*
+                    _ => Err(format!("Unknown {} `{}`", stringify!(#ident), value).into()),
+                }
+            }
+        }
+    )
+}
+
+// The discriminant of the variants, `Status::Active as i32`.
+fn i32_impl(ast: &DeriveInput, db: TokenStream, variants: &[(syn::Ident, String)]) -> TokenStream {
+    let ident = &ast.ident;
+    let encode = variants.iter().map(|(variant, _)| quote!(Self::#variant => Self::#variant as i32,));
+    let decode = variants.iter().map(|(variant, _)| quote!(v if v == Self::#variant as i32 => Ok(Self::#variant),));
+
+    quote!(
+        impl cherry::sqlx::Type<#db> for #ident {
+            fn type_info() -> <#db as cherry::sqlx::Database>::TypeInfo {
+                <i32 as cherry::sqlx::Type<#db>>::type_info()
+            }
+
+            fn compatible(ty: &<#db as cherry::sqlx::Database>::TypeInfo) -> bool {
+                <i32 as cherry::sqlx::Type<#db>>::compatible(ty)
+            }
+        }
+
+        impl<'q> cherry::sqlx::Encode<'q, #db> for #ident {
+            fn encode_by_ref(&self, buf: &mut <#db as cherry::sqlx::database::HasArguments<'q>>::ArgumentBuffer)
+                -> cherry::sqlx::encode::IsNull {
+                let value: i32 = match self { #(#encode)* };
+                <i32 as cherry::sqlx::Encode<'q, #db>>::encode(value, buf)
+            }
+        }
+
+        impl<'r> cherry::sqlx::Decode<'r, #db> for #ident {
+            fn decode(value: <#db as cherry::sqlx::database::HasValueRef<'r>>::ValueRef)
+                -> Result<Self, cherry::sqlx::error::BoxDynError> {
+                let value = <i32 as cherry::sqlx::Decode<'r, #db>>::decode(value)?;
+                match value {
+                    #(#decode)*
+                    _ => Err(format!("Unknown {} `{}`", stringify!(#ident), value).into()),
+                }
+            }
+        }
+    )
+}
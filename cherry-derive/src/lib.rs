@@ -1,8 +1,21 @@
 use proc_macro::TokenStream;
 
+pub(crate) mod attr;
 pub(crate) mod derive_cherry;
+pub(crate) mod derive_enum;
+pub(crate) mod relation;
+pub(crate) mod verify;
 
 #[proc_macro_derive(Cherry, attributes(cherry))]
 pub fn derive_cherry(input: TokenStream) -> TokenStream {
     derive_cherry::derive(syn::parse_macro_input!(input))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(CherryEnum, attributes(cherry))]
+pub fn derive_cherry_enum(input: TokenStream) -> TokenStream {
+    derive_enum::derive(syn::parse_macro_input!(input))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
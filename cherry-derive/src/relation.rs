@@ -0,0 +1,262 @@
+use heck::SnakeCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, LitStr, Path};
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+
+use crate::attr::{string, unknown};
+use crate::derive_cherry::Field;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Kind {
+    HasMany,
+    BelongsTo,
+    ManyToMany,
+}
+
+// `#[cherry(has_many(Post, foreign_key = "user_id"))]`, the foreign key is a column of `Post`.
+// `#[cherry(belongs_to(User, foreign_key = "user_id"))]`, the foreign key is a column of `Self`.
+// `#[cherry(many_to_many(Tag, through = "post_tags", left = "post_id", right = "tag_id"))]`, the
+// foreign key is `left`, the column of the join table referencing `Self`.
+pub(crate) struct Relation {
+    pub(crate) kind: Kind,
+    pub(crate) target: Path,
+    pub(crate) foreign_key: LitStr,
+    pub(crate) through: Option<(LitStr, LitStr)>, // the join table and `right`
+    pub(crate) name: syn::Ident, // the method, `posts` or `user` by default
+}
+
+impl Relation {
+    const KEYS: &'static [&'static str] = &["foreign_key", "name"];
+    const THROUGH_KEYS: &'static [&'static str] = &["through", "left", "right", "name"];
+
+    pub(crate) fn parse(kind: Kind, meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut target: Option<Path> = None;
+        let mut foreign_key = None;
+        let mut through = None;
+        let mut right = None;
+        let mut name: Option<LitStr> = None;
+        meta.parse_nested_meta(|inner| {
+            // the entity comes first, without a value
+            if target.is_none() && !inner.input.peek(syn::Token![=]) {
+                target = Some(inner.path);
+                return Ok(());
+            }
+            match (kind, inner.path.get_ident().map(|ident| ident.to_string()).as_deref()) {
+                (Kind::ManyToMany, Some("through")) => through = Some(string(&inner)?),
+                (Kind::ManyToMany, Some("left")) => foreign_key = Some(string(&inner)?),
+                (Kind::ManyToMany, Some("right")) => right = Some(string(&inner)?),
+                (Kind::ManyToMany, Some("name")) => name = Some(string(&inner)?),
+                (Kind::ManyToMany, _) => return Err(unknown(&inner, Self::THROUGH_KEYS)),
+                (_, Some("foreign_key")) => foreign_key = Some(string(&inner)?),
+                (_, Some("name")) => name = Some(string(&inner)?),
+                _ => return Err(unknown(&inner, Self::KEYS)),
+            }
+            Ok(())
+        })?;
+
+        let target = target.ok_or_else(|| meta.error("The related entity is missing, `has_many(Post, ..)`."))?;
+        let missing = |key: &str| meta.error(format!("`{} = \"..\"` is missing.", key));
+        let through = match kind {
+            Kind::ManyToMany => Some((
+                through.ok_or_else(|| missing("through"))?,
+                right.ok_or_else(|| missing("right"))?,
+            )),
+            _ => None,
+        };
+        let foreign_key = match kind {
+            Kind::ManyToMany => foreign_key.ok_or_else(|| missing("left"))?,
+            _ => foreign_key.ok_or_else(|| missing("foreign_key"))?,
+        };
+        let name = match name {
+            Some(lit) => syn::Ident::new(&lit.value(), lit.span()),
+            None => {
+                let ident = &target.segments.last().expect("a path has segments").ident;
+                let name = ident.to_string().to_snake_case();
+                match kind {
+                    Kind::HasMany | Kind::ManyToMany => format_ident!("{}s", name, span = ident.span()),
+                    Kind::BelongsTo => format_ident!("{}", name, span = ident.span()),
+                }
+            }
+        };
+        Ok(Self { kind, target, foreign_key, through, name })
+    }
+}
+
+// The methods returning the select of the related entities, for every database.
+pub(crate) fn relation_impl(ast: &DeriveInput, fields: &[Field], relations: &[Relation]) -> syn::Result<TokenStream> {
+    let ident = &ast.ident;
+    let vis = &ast.vis;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let methods = relations.iter().map(|relation| {
+        let (target, name, foreign_key) = (&relation.target, &relation.name, &relation.foreign_key);
+        match relation.kind {
+            // WHERE post.user_id = user.id
+            Kind::HasMany => {
+                let key = single_key(ast, fields, relation)?;
+                let (member, ty) = (&key.member, &key.ty);
+                Ok(quote!(
+                    #vis fn #name<'cherry, DB>(&'cherry self) -> cherry::Query<'cherry, #target, DB>
+                        where
+                            DB: cherry::sqlx::Database,
+                            #target: cherry::Cherry<'cherry, DB>,
+                            &'cherry #ty: cherry::sqlx::Encode<'cherry, DB> + cherry::sqlx::Type<DB> + Send {
+                        cherry::clause::Where::and_eq(<#target as cherry::Cherry<'cherry, DB>>::select(), #foreign_key, &self.#member)
+                    }
+                ))
+            }
+            // WHERE user.id = post.user_id
+            Kind::BelongsTo => {
+                let field = fields.iter().find(|f| f.column == foreign_key.value()).ok_or_else(|| syn::Error::new(
+                    foreign_key.span(),
+                    format!("No field has the foreign key column `{}`.", foreign_key.value()),
+                ))?;
+                let member = &field.member;
+                // a nullable foreign key, no query without a related entity
+                if let Some(ty) = option_inner(&field.ty) {
+                    return Ok(quote!(
+                        #vis fn #name<'cherry, DB>(&'cherry self) -> Option<cherry::Query<'cherry, #target, DB>>
+                            where
+                                DB: cherry::sqlx::Database,
+                                #target: cherry::PrimaryKey<'cherry, DB, Key = #ty>,
+                                #ty: Clone {
+                            self.#member.clone().map(<#target as cherry::PrimaryKey<'cherry, DB>>::find)
+                        }
+                    ));
+                }
+                let ty = &field.ty;
+                Ok(quote!(
+                    #vis fn #name<'cherry, DB>(&'cherry self) -> cherry::Query<'cherry, #target, DB>
+                        where
+                            DB: cherry::sqlx::Database,
+                            #target: cherry::PrimaryKey<'cherry, DB, Key = #ty>,
+                            #ty: Clone {
+                        <#target as cherry::PrimaryKey<'cherry, DB>>::find(self.#member.clone())
+                    }
+                ))
+            }
+            // WHERE tag.id IN (SELECT tag_id FROM post_tags WHERE post_id = post.id)
+            Kind::ManyToMany => {
+                single_key(ast, fields, relation)?;
+                let attach = format_ident!("attach_{}", name);
+                let detach = format_ident!("detach_{}", name);
+                let sync = format_ident!("sync_{}", name);
+                let key = quote!(<#target as cherry::PrimaryKey<'cherry, DB>>::Key);
+                let bounds = quote!(
+                    DB: cherry::sqlx::Database,
+                    Self: cherry::ManyToMany<'cherry, #target, DB>,
+                    #target: cherry::SingleKey<'cherry, DB>
+                );
+                Ok(quote!(
+                    #vis fn #name<'cherry, DB>(&self) -> cherry::Query<'cherry, #target, DB>
+                        where #bounds {
+                        <Self as cherry::ManyToMany<'cherry, #target, DB>>::related(self)
+                    }
+
+                    #vis fn #attach<'cherry, DB>(&self, keys: impl IntoIterator<Item = #key>) -> cherry::Query<'cherry, Self, DB>
+                        where #bounds {
+                        <Self as cherry::ManyToMany<'cherry, #target, DB>>::attach(self, keys)
+                    }
+
+                    #vis fn #detach<'cherry, DB>(&self, keys: impl IntoIterator<Item = #key>) -> cherry::Query<'cherry, Self, DB>
+                        where #bounds {
+                        <Self as cherry::ManyToMany<'cherry, #target, DB>>::detach(self, keys)
+                    }
+
+                    #vis fn #sync<'cherry, 'e, DB>(
+                        &self,
+                        keys: impl IntoIterator<Item = #key>,
+                        a: impl cherry::sqlx::Acquire<'e, Database = DB> + Send + 'e,
+                    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), cherry::Error>> + Send + 'e>>
+                        where
+                            'cherry: 'e,
+                            #bounds,
+                            #key: Clone,
+                            for<'c> &'c mut <DB as cherry::sqlx::Database>::Connection: cherry::sqlx::Executor<'c, Database = DB>,
+                            cherry::Query<'cherry, Self, DB>: cherry::QueryExecutor<'cherry, Self, DB> {
+                        <Self as cherry::ManyToMany<'cherry, #target, DB>>::sync(self, keys, a)
+                    }
+                ))
+            }
+        }
+    }).collect::<syn::Result<Vec<TokenStream>>>()?;
+
+    // `HasMany` and `ManyToMany` for every database the related entity is stored in
+    let mut generics = ast.generics.clone();
+    generics.params.insert(0, syn::parse_quote!('cherry));
+    generics.params.push(syn::parse_quote!(DB));
+    generics.make_where_clause().predicates.push(syn::parse_quote!(DB: cherry::sqlx::Database));
+    let (has_many_generics, _, has_many_where) = generics.split_for_impl();
+    let has_many = relations.iter().filter(|r| r.kind == Kind::HasMany).map(|relation| {
+        let (target, foreign_key) = (&relation.target, &relation.foreign_key);
+        let predicates = has_many_where.map(|w| &w.predicates);
+        quote!(
+            impl #has_many_generics cherry::HasMany<'cherry, #target, DB> for #ident #ty_generics
+                where
+                    #predicates,
+                    Self: cherry::PrimaryKey<'cherry, DB>,
+                    #target: cherry::Cherry<'cherry, DB> {
+                fn foreign_key() -> &'static str {
+                    #foreign_key
+                }
+            }
+        )
+    });
+
+    let many_to_many = relations.iter().filter_map(|relation| relation.through.as_ref().map(|t| (relation, t))).map(|(relation, (through, right))| {
+        let (target, left) = (&relation.target, &relation.foreign_key);
+        let predicates = has_many_where.map(|w| &w.predicates);
+        quote!(
+            impl #has_many_generics cherry::ManyToMany<'cherry, #target, DB> for #ident #ty_generics
+                where
+                    #predicates,
+                    Self: cherry::SingleKey<'cherry, DB>,
+                    #target: cherry::SingleKey<'cherry, DB> {
+                fn through() -> (&'static str, &'static str, &'static str) {
+                    (#through, #left, #right)
+                }
+            }
+        )
+    });
+
+    Ok(quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#methods)*
+        }
+
+        #(#has_many)*
+
+        #(#many_to_many)*
+    ))
+}
+
+// The primary key referenced by the foreign key of the related entities.
+fn single_key<'f>(ast: &DeriveInput, fields: &'f [Field], relation: &Relation) -> syn::Result<&'f Field> {
+    let mut keys = fields.iter().filter(|f| f.primary_key);
+    match (keys.next(), keys.next()) {
+        (Some(key), None) => Ok(key),
+        _ => Err(syn::Error::new(
+            relation.target.span(),
+            format!("`{}` needs a single `primary_key` field to have related entities.", ast.ident),
+        )),
+    }
+}
+
+// `K` of an `Option<K>` field.
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if segment.ident == "Option" && args.args.len() == 1 => {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(ty)) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+
+use crate::derive_cherry::{Field, params, uses_params};
+
+// Check the fields against the DDL of a schema file, `#[cherry(verify = "tests/migrations.sql")]`.
+// The path is relative to the crate root. Returns spanned compile errors, plus an `include_str!`
+// so the entity is checked again whenever the file changes. The columns of the flattened fields
+// are unknown here, they're checked in a constant evaluation, see `cherry::verify`.
+pub(crate) fn verify(ast: &syn::DeriveInput, table: &str, fields: &[Field], path: &str) -> TokenStream {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let file = Path::new(&root).join(path);
+    let sql = match std::fs::read_to_string(&file) {
+        Ok(sql) => sql,
+        Err(e) => {
+            let message = format!("Cherry can't read the schema file `{}`: {}", file.display(), e);
+            return syn::Error::new_spanned(&ast.ident, message).to_compile_error();
+        }
+    };
+
+    let tables = parse_schema(&sql);
+    let columns = match tables.get(&table.to_lowercase()) {
+        Some(columns) => columns,
+        None => {
+            let message = format!("Table `{}` not found in `{}`, `verify = false` skips a struct without a table.", table, path);
+            return syn::Error::new_spanned(&ast.ident, message).to_compile_error();
+        }
+    };
+
+    let errors = fields.iter()
+        .filter(|f| !f.skip && !f.flatten)
+        .filter_map(|f| match columns.get(&f.column.to_lowercase()) {
+            None => Some(syn::Error::new_spanned(
+                &f.member,
+                format!("Column `{}` not found in table `{}` of `{}`.", f.column, table, path),
+            )),
+            Some(sql_type) => {
+                let ty = f.ty.to_token_stream().to_string();
+                match compatible(&ty, sql_type) {
+                    true => None,
+                    false => Some(syn::Error::new_spanned(
+                        &f.ty,
+                        format!("`{}` is incompatible with the column `{}` declared as `{}`.", ty, f.column, sql_type),
+                    )),
+                }
+            }
+        })
+        .map(|e| e.to_compile_error())
+        .collect::<TokenStream>();
+
+    // a constant can't name the generic parameters of the struct
+    let params = params(&ast.generics);
+    let mut schema = columns.keys().collect::<Vec<&String>>();
+    schema.sort();
+    let flattened = fields.iter()
+        .filter(|f| !f.skip && f.flatten && !uses_params(f.ty.to_token_stream(), &params))
+        .map(|f| {
+            let (ty, prefix) = (&f.ty, &f.prefix);
+            quote_spanned!(ty.span() =>
+                const _: () = cherry::verify::flattened(&[ #(#schema),* ], #prefix, <#ty>::__CHERRY_COLUMNS, #table, #path);
+            )
+        })
+        .collect::<TokenStream>();
+
+    let file = file.display().to_string();
+    quote!(
+        const _: &str = include_str!(#file);
+        #errors
+        #flattened
+    )
+}
+
+// The columns of every table, lower cased name -> declared type.
+// Understands `CREATE TABLE` and `ALTER TABLE .. ADD [COLUMN]`, other statements are ignored.
+fn parse_schema(sql: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut tables: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+    for statement in tokenize(sql).split(|t| t == ";") {
+        let words = statement.iter().map(|t| t.to_uppercase()).collect::<Vec<String>>();
+        let mut i = 0;
+        let mut next = |keywords: &[&str]| match words.get(i) {
+            Some(w) if keywords.contains(&w.as_str()) => { i += 1; true }
+            _ => false,
+        };
+
+        if next(&["CREATE"]) {
+            while next(&["TEMP", "TEMPORARY", "UNLOGGED", "GLOBAL", "LOCAL"]) {}
+            if !next(&["TABLE"]) {
+                continue;
+            }
+            if next(&["IF"]) && !(next(&["NOT"]) && next(&["EXISTS"])) {
+                continue;
+            }
+            let (name, rest) = table_name(&statement[i..]);
+            if rest.first().map(String::as_str) != Some("(") {
+                continue; // CREATE TABLE .. AS SELECT
+            }
+            let columns = split_items(&rest[1..]).into_iter()
+                .filter_map(column_definition)
+                .collect();
+            tables.insert(name, columns);
+        } else if next(&["ALTER"]) && next(&["TABLE"]) {
+            next(&["ONLY"]);
+            if next(&["IF"]) && !next(&["EXISTS"]) {
+                continue;
+            }
+            let (name, rest) = table_name(&statement[i..]);
+            let mut rest = rest;
+            if rest.first().map(|t| t.to_uppercase()).as_deref() != Some("ADD") {
+                continue;
+            }
+            rest = &rest[1..];
+            if rest.first().map(|t| t.to_uppercase()).as_deref() == Some("COLUMN") {
+                rest = &rest[1..];
+            }
+            if let Some((column, ty)) = column_definition(rest) {
+                tables.entry(name).or_default().insert(column, ty);
+            }
+        }
+    }
+
+    tables
+}
+
+// `schema.table` keeps the last part, returns the tokens after the name.
+fn table_name(tokens: &[String]) -> (String, &[String]) {
+    let mut i = 0;
+    while tokens.get(i + 1).map(String::as_str) == Some(".") {
+        i += 2;
+    }
+    let name = tokens.get(i).map(|t| unquote(t).to_lowercase()).unwrap_or_default();
+    (name, tokens.get(i + 1..).unwrap_or_default())
+}
+
+// The comma separated items inside the parentheses of `CREATE TABLE t ( .. )`.
+fn split_items(tokens: &[String]) -> Vec<&[String]> {
+    let mut items = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, token) in tokens.iter().enumerate() {
+        match token.as_str() {
+            "(" => depth += 1,
+            ")" if depth == 0 => {
+                items.push(&tokens[start..i]);
+                return items;
+            }
+            ")" => depth -= 1,
+            "," if depth == 0 => {
+                items.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&tokens[start..]);
+    items
+}
+
+// `name type(args) constraints..`, `None` for table constraints.
+fn column_definition(item: &[String]) -> Option<(String, String)> {
+    const CONSTRAINTS: &[&str] = &[
+        "CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN", "KEY", "INDEX", "FULLTEXT", "SPATIAL",
+        "EXCLUDE", "LIKE", "PERIOD",
+    ];
+    const MODIFIERS: &[&str] = &[
+        "NOT", "NULL", "DEFAULT", "PRIMARY", "UNIQUE", "REFERENCES", "CHECK", "CONSTRAINT", "COLLATE",
+        "AUTO_INCREMENT", "AUTOINCREMENT", "GENERATED", "AS", "ON", "COMMENT", "IDENTITY",
+    ];
+
+    let name = item.first()?;
+    if CONSTRAINTS.contains(&name.to_uppercase().as_str()) {
+        return None;
+    }
+
+    let mut ty = vec![];
+    let mut depth = 0;
+    for token in &item[1..] {
+        match token.as_str() {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            _ if depth > 0 => {}
+            _ if MODIFIERS.contains(&token.to_uppercase().as_str()) => break,
+            _ => ty.push(token.to_uppercase()),
+        }
+    }
+
+    Some((unquote(name).to_lowercase(), ty.join(" ")))
+}
+
+fn unquote(token: &str) -> &str {
+    match token.chars().next() {
+        Some('"') | Some('`') | Some('[') if token.len() > 1 => &token[1..token.len() - 1],
+        _ => token,
+    }
+}
+
+// Words, quoted identifiers, string literals and single punctuation characters.
+// Comments are dropped.
+fn tokenize(sql: &str) -> Vec<String> {
+    let chars = sql.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '"' | '`' | '\'' | '[' => {
+                let end = if c == '[' { ']' } else { c };
+                i += 1;
+                while i < chars.len() && chars[i] != end {
+                    i += 1;
+                }
+                i += 1;
+                tokens.push(chars[start..i.min(chars.len())].iter().collect());
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '$' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                    i += 1;
+                }
+                tokens.push(chars[start..i].iter().collect());
+            }
+            _ => {
+                i += 1;
+                tokens.push(c.to_string());
+            }
+        }
+    }
+
+    tokens
+}
+
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Integer,
+    Float,
+    Text,
+    Bool,
+    Blob,
+    Time,
+}
+
+// Loose check by kind, `None` when cherry has no idea of the type (custom types, enums, json..).
+fn compatible(rust_type: &str, sql_type: &str) -> bool {
+    match (rust_kind(rust_type), sql_kind(sql_type)) {
+        (Some(rust), Some(sql)) => rust == sql || matches!(
+            (rust, sql),
+            // no boolean type in sqlite and mysql, dates are often stored as text in sqlite
+            (Kind::Bool, Kind::Integer) | (Kind::Time, Kind::Text) | (Kind::Float, Kind::Integer)
+        ),
+        _ => true,
+    }
+}
+
+fn rust_kind(ty: &str) -> Option<Kind> {
+    let ty = ty.replace(' ', "");
+    let ty = ty.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')).unwrap_or(&ty);
+    if ty == "Vec<u8>" || ty == "&[u8]" {
+        return Some(Kind::Blob);
+    }
+    // the last path segment without generics, `chrono::DateTime<Utc>` -> `DateTime`
+    let ty = ty.split('<').next().unwrap_or_default();
+    let ty = ty.trim_start_matches('&').rsplit("::").next().unwrap_or_default();
+    match ty {
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => Some(Kind::Integer),
+        "f32" | "f64" => Some(Kind::Float),
+        "String" | "str" => Some(Kind::Text),
+        "bool" => Some(Kind::Bool),
+        "NaiveDate" | "NaiveTime" | "NaiveDateTime" | "DateTime" | "Date" | "Time" | "PrimitiveDateTime"
+        | "OffsetDateTime" => Some(Kind::Time),
+        _ => None,
+    }
+}
+
+fn sql_kind(ty: &str) -> Option<Kind> {
+    let ty = ty.split_whitespace().next()?;
+    let kind = match ty {
+        "BOOL" | "BOOLEAN" => Kind::Bool,
+        "DATE" | "TIME" | "DATETIME" | "TIMESTAMP" | "TIMESTAMPTZ" | "TIMETZ" | "INTERVAL" => Kind::Time,
+        "REAL" | "FLOAT" | "FLOAT4" | "FLOAT8" | "DOUBLE" | "NUMERIC" | "DECIMAL" => Kind::Float,
+        "BLOB" | "BYTEA" | "BINARY" | "VARBINARY" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" => Kind::Blob,
+        _ if ty.contains("INT") || ty.contains("SERIAL") => Kind::Integer,
+        _ if ty.contains("CHAR") || ty.contains("TEXT") || ty.contains("CLOB") => Kind::Text,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_schema() {
+        let tables = parse_schema(r#"
+            -- users
+            CREATE TABLE IF NOT EXISTS public."user" (
+                id BIGSERIAL PRIMARY KEY,
+                `name` VARCHAR(64) NOT NULL DEFAULT 'a, b',
+                price DECIMAL(10, 2),
+                CONSTRAINT name_unique UNIQUE (name)
+            );
+            /* later */
+            ALTER TABLE user ADD COLUMN created_at TIMESTAMP WITH TIME ZONE;
+            CREATE INDEX user_name ON user (name);
+        "#);
+
+        let user = &tables["user"];
+        assert_eq!(4, user.len());
+        assert_eq!("BIGSERIAL", user["id"]);
+        assert_eq!("VARCHAR", user["name"]);
+        assert_eq!("DECIMAL", user["price"]);
+        assert_eq!("TIMESTAMP WITH TIME ZONE", user["created_at"]);
+    }
+
+    #[test]
+    fn test_compatible() {
+        assert!(compatible("u32", "INT UNSIGNED"));
+        assert!(compatible("Option < String >", "TEXT"));
+        assert!(compatible("bool", "TINYINT"));
+        assert!(compatible("chrono :: NaiveDate", "TEXT"));
+        assert!(compatible("DateTime < chrono :: Utc >", "TIMESTAMPTZ"));
+        assert!(compatible("Status", "TEXT"));
+        assert!(!compatible("String", "INTEGER"));
+        assert!(!compatible("i64", "VARCHAR"));
+        assert!(!compatible("Vec < u8 >", "TEXT"));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::dialect::Dialect;
 
+// the fields are read once `to_sql` is written
+#[allow(dead_code)]
 pub struct Insert {
     dialect: Dialect,
     table: String,
@@ -10,7 +12,7 @@ pub struct Insert {
 }
 
 impl Insert {
-    pub fn from(dialect: Dialect, table: String, columns: Vec<String>) -> Self {
+    pub fn from(_dialect: Dialect, _table: String, _columns: Vec<String>) -> Self {
         todo!()
     }
 
@@ -1,7 +1,7 @@
-mod insert;
-mod select;
-mod dialect;
-mod restrict;
+pub mod insert;
+pub mod select;
+pub mod dialect;
+pub mod restrict;
 mod delete;
 mod update;
 
@@ -1,12 +1,14 @@
 use crate::dialect::Dialect;
 
+// the fields are read once `to_sql` is written
+#[allow(dead_code)]
 pub struct Select {
     table: String,
     dialect: Dialect,
 }
 
 impl Select {
-    pub fn from(table: String, dialect: Dialect) -> Self {
+    pub fn from(_table: String, _dialect: Dialect) -> Self {
         todo!()
     }
 
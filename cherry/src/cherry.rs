@@ -1,5 +1,13 @@
 use crate::query::Query;
 
+// The values of an insert row, bound when the insert is executed. The fields are borrowed, the
+// entity itself doesn't have to be `Sync`.
+pub type InsertValues<'a, DB> = Box<dyn FnOnce(&mut <DB as sqlx::database::HasArguments<'a>>::Arguments) + Send + 'a>;
+
+// Whether the tenant of an insert row is the one of the running `TenantId` scope, checked when the
+// insert is executed. The tenant field is borrowed like the insert values.
+pub type TenantCheck<'a> = Box<dyn FnOnce() -> bool + Send + 'a>;
+
 pub trait Cherry<'a, DB>: Sized + Send + Unpin
     where
         DB: sqlx::Database {
@@ -9,10 +17,99 @@ pub trait Cherry<'a, DB>: Sized + Send + Unpin
     // field name -> column name
     fn columns() -> Vec<(&'static str, &'static str)>;
 
-    fn arguments(&'a self, arguments: &mut <DB as sqlx::database::HasArguments<'a>>::Arguments);
+    // columns written by inserts, without the ones generated by the database
+    fn insert_columns() -> Vec<(&'static str, &'static str)> {
+        Self::columns()
+    }
+
+    // the values of `insert_columns()`, bound when the insert is executed, the only values an
+    // entity has to bind
+    fn insert_values(&'a self) -> InsertValues<'a, DB>;
+
+    // columns written by `update_by_pk()`, without the primary key and the generated columns
+    fn update_columns() -> Vec<(&'static str, &'static str)> {
+        Self::columns()
+    }
+
+    // bind the values of `update_columns()`, the insert values by default
+    fn update_arguments(&'a self, arguments: &mut <DB as sqlx::database::HasArguments<'a>>::Arguments) {
+        self.insert_values()(arguments)
+    }
+
+    // bind the value of one column, false if the entity has no such column
+    fn column_arguments(&'a self, _column: &str, _arguments: &mut <DB as sqlx::database::HasArguments<'a>>::Arguments)
+        -> bool {
+        false
+    }
+
+    // the column of `#[cherry(version)]`, for optimistic locking
+    fn version() -> Option<&'static str> {
+        None
+    }
+
+    // the column of `#[cherry(created_at)]`, set by inserts only
+    fn created_at() -> Option<&'static str> {
+        None
+    }
+
+    // the column of `#[cherry(updated_at)]`, set by inserts and every update
+    fn updated_at() -> Option<&'static str> {
+        None
+    }
+
+    // bind the current time as the value of `updated_at()`
+    fn touch_arguments(_arguments: &mut <DB as sqlx::database::HasArguments<'a>>::Arguments) {
+    }
+
+    // the column of `#[cherry(soft_delete = "..")]`, `delete()` sets it to the current time
+    fn soft_delete() -> Option<&'static str> {
+        None
+    }
+
+    // bind the current time as the value of `soft_delete()`
+    fn soft_delete_arguments(_arguments: &mut <DB as sqlx::database::HasArguments<'a>>::Arguments) {
+    }
+
+    // the raw SQL conditions of `#[cherry(scope = "..")]`, unchecked, AND-ed with the selects,
+    // updates and deletes
+    fn scopes() -> &'static [&'static str] {
+        &[]
+    }
+
+    // the column of `#[cherry(tenant)]`, filtered by the tenant of `TenantId::scope`
+    fn tenant() -> Option<&'static str> {
+        None
+    }
+
+    // bind the current tenant, false if out of a `TenantId` scope
+    fn tenant_arguments(_arguments: &mut <DB as sqlx::database::HasArguments<'a>>::Arguments) -> bool {
+        true
+    }
+
+    // the check of the tenant of the row, true out of a `TenantId` scope
+    fn tenant_check(&'a self) -> TenantCheck<'a> {
+        Box::new(|| true)
+    }
+
+    // primary key column names, empty if not declared
+    fn primary_key() -> &'static [&'static str] {
+        &[]
+    }
+
+    // bind the primary key values, in the order of `primary_key()`
+    fn primary_key_arguments(&'a self, _arguments: &mut <DB as sqlx::database::HasArguments<'a>>::Arguments) {
+    }
 
     fn from_row(row: &<DB as sqlx::Database>::Row) -> Result<Self, crate::Error>;
 
+    // decode from the columns named `{prefix}{column}`, for flattened fields
+    fn from_row_prefixed(row: &<DB as sqlx::Database>::Row, prefix: &str) -> Result<Self, crate::Error> {
+        match prefix.is_empty() {
+            true => Self::from_row(row),
+            _ => Err(anyhow::anyhow!("`{}` can't be decoded with the column prefix `{}`", Self::table(), prefix)),
+        }
+    }
+
     fn insert(&'a self) -> Query<'a, Self, DB> {
         Query::new_insert(self)
     }
@@ -29,19 +126,42 @@ pub trait Cherry<'a, DB>: Sized + Send + Unpin
         Query::new_select()
     }
 
+    // An UPDATE setting `soft_delete()` if declared, a DELETE otherwise.
     fn delete() -> Query<'a, Self, DB> {
         Query::new_delete()
     }
+
+    // Unset `soft_delete()` of the deleted rows.
+    fn restore() -> Query<'a, Self, DB> {
+        Query::new_restore()
+    }
+
+    // A DELETE, even with `soft_delete()`, of the deleted rows too.
+    fn force_delete() -> Query<'a, Self, DB> {
+        Query::new_force_delete()
+    }
+}
+
+// Prefix the columns of a flattened field. Every prefixed name is leaked, call it once.
+#[doc(hidden)]
+pub fn prefix_columns(columns: Vec<(&'static str, &'static str)>, prefix: &str)
+                      -> Vec<(&'static str, &'static str)> {
+    match prefix.is_empty() {
+        true => columns,
+        _ => columns.into_iter()
+            .map(|(f, c)| (f, &*Box::leak(format!("{}{}", prefix, c).into_boxed_str())))
+            .collect(),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use anyhow::Error;
 
-    use crate::Cherry;
+    use crate::{Cherry, InsertValues};
     use crate::sqlx::{Database, Sqlite};
-    use crate::sqlx::database::HasArguments;
 
+    #[allow(dead_code)]
     struct Example {
         id: u32,
     }
@@ -55,12 +175,14 @@ mod tests {
             todo!()
         }
 
-        fn arguments(&'a self, arguments: &mut <Sqlite as HasArguments<'a>>::Arguments) {
-            use sqlx::Arguments;
-            arguments.add(1);
+        fn insert_values(&'a self) -> InsertValues<'a, Sqlite> {
+            Box::new(|arguments| {
+                use sqlx::Arguments;
+                arguments.add(1);
+            })
         }
 
-        fn from_row(row: &<Sqlite as Database>::Row) -> Result<Self, Error> {
+        fn from_row(_row: &<Sqlite as Database>::Row) -> Result<Self, Error> {
             todo!()
         }
 
@@ -1,5 +1,6 @@
 use sqlx::Database;
 
+use crate::column::ColumnName;
 use crate::provider::Provider;
 use crate::sql::InsertOnConflict;
 
@@ -22,8 +23,9 @@ pub trait InsertConflict<'a, DB>: Provider<'a, DB> + Sized where DB: Database {
     }
     
     #[cfg(any(feature = "sqlite", feature = "postgres"))]
-    fn conflict_column(mut self, column: &'a str) -> Self {
-        self.sql_builder().add_conflict_column(column);
+    fn conflict_column<C>(mut self, column: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_conflict_column(column.column_name());
         self
     }
     
@@ -1,12 +1,14 @@
 use sqlx::Database;
 
+use crate::column::ColumnName;
 use crate::provider::Provider;
 use crate::sql::select_column::Column;
 
 pub trait SelectColumn<'a, DB>: Provider<'a, DB> + Sized where DB: Database {
 
-    fn column(mut self, c: &'a str) -> Self {
-        self.sql_builder().add_select_column(Column::Column(c));
+    fn column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_select_column(Column::Column(c.column_name()));
         self
     }
 
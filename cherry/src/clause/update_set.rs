@@ -1,19 +1,23 @@
 use sqlx::{Database, Encode, Type};
 
+use crate::column::{AcceptValue, ColumnName};
 use crate::provider::Provider;
 use crate::sql::update_set_clause::UpdateSetSection;
 
 pub trait UpdateSet<'a, DB>: Provider<'a, DB> + Sized where DB: Database {
 
-    fn set<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn set<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_update_section(UpdateSetSection::SetValue(c));
+        self.sql_builder().add_update_section(UpdateSetSection::SetValue(c.column_name()));
         self
     }
 
-    fn set_column(mut self, c: &'a str) -> Self {
-        self.sql_builder().add_update_section(UpdateSetSection::SetColumn(c));
+    fn set_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_update_section(UpdateSetSection::SetColumn(c.column_name()));
         self
     }
 
@@ -21,7 +25,6 @@ pub trait UpdateSet<'a, DB>: Provider<'a, DB> + Sized where DB: Database {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
 
     #[test]
     fn test() {
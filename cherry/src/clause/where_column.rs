@@ -1,148 +1,177 @@
 use sqlx::Database;
 
+use crate::column::ColumnName;
 use crate::clause::where_value::Where;
 use crate::sql::where_condition::Condition;
 
 pub trait WhereColumn<'a, DB>: Where<'a, DB> + Sized where DB: Database {
 
-    fn and_eq_column(mut self, c: &'a str) -> Self {
+    fn and_eq_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_eq_column_ref(c);
         self
     }
 
-    fn and_eq_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndEqColumn(c));
+    fn and_eq_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndEqColumn(c.column_name()));
         self
     }
 
-    fn or_eq_column(mut self, c: &'a str) -> Self {
+    fn or_eq_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_eq_column_ref(c);
         self
     }
 
-    fn or_eq_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrEqColumn(c));
+    fn or_eq_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrEqColumn(c.column_name()));
         self
     }
 
-    fn and_ge_column(mut self, c: &'a str) -> Self {
+    fn and_ge_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_ge_column_ref(c);
         self
     }
 
-    fn and_ge_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndGeColumn(c));
+    fn and_ge_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndGeColumn(c.column_name()));
         self
     }
 
-    fn or_ge_column(mut self, c: &'a str) -> Self {
+    fn or_ge_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_ge_column_ref(c);
         self
     }
 
-    fn or_ge_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrGeColumn(c));
+    fn or_ge_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrGeColumn(c.column_name()));
         self
     }
 
-    fn and_gt_column(mut self, c: &'a str) -> Self {
+    fn and_gt_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_gt_column_ref(c);
         self
     }
 
-    fn and_gt_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndGtColumn(c));
+    fn and_gt_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndGtColumn(c.column_name()));
         self
     }
 
-    fn or_gt_column(mut self, c: &'a str) -> Self {
+    fn or_gt_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_gt_column_ref(c);
         self
     }
 
-    fn or_gt_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrGtColumn(c));
+    fn or_gt_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrGtColumn(c.column_name()));
         self
     }
 
-    fn and_le_column(mut self, c: &'a str) -> Self {
+    fn and_le_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_le_column_ref(c);
         self
     }
 
-    fn and_le_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndLeColumn(c));
+    fn and_le_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndLeColumn(c.column_name()));
         self
     }
 
-    fn or_le_column(mut self, c: &'a str) -> Self {
+    fn or_le_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_le_column_ref(c);
         self
     }
 
-    fn or_le_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrLeColumn(c));
+    fn or_le_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrLeColumn(c.column_name()));
         self
     }
 
-    fn and_lt_column(mut self, c: &'a str) -> Self {
+    fn and_lt_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_lt_column_ref(c);
         self
     }
 
-    fn and_lt_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndLtColumn(c));
+    fn and_lt_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndLtColumn(c.column_name()));
         self
     }
 
-    fn or_lt_column(mut self, c: &'a str) -> Self {
+    fn or_lt_column<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_lt_column_ref(c);
         self
     }
 
-    fn or_lt_column_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrLtColumn(c));
+    fn or_lt_column_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrLtColumn(c.column_name()));
         self
     }
 
-    fn and_column_is_null(mut self, c: &'a str) -> Self {
+    fn and_column_is_null<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_column_is_null_ref(c);
         self
     }
 
-    fn and_column_is_null_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndColumnIsNull(c));
+    fn and_column_is_null_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndColumnIsNull(c.column_name()));
         self
     }
 
-    fn or_column_is_null(mut self, c: &'a str) -> Self {
+    fn or_column_is_null<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_column_is_null_ref(c);
         self
     }
 
-    fn or_column_is_null_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrColumnIsNull(c));
+    fn or_column_is_null_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrColumnIsNull(c.column_name()));
         self
     }
 
 
-    fn and_column_is_not_null(mut self, c: &'a str) -> Self {
+    fn and_column_is_not_null<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_column_is_not_null_ref(c);
         self
     }
 
-    fn and_column_is_not_null_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndColumnIsNotNull(c));
+    fn and_column_is_not_null_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndColumnIsNotNull(c.column_name()));
         self
     }
 
-    fn or_column_is_not_null(mut self, c: &'a str) -> Self {
+    fn or_column_is_not_null<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_column_is_not_null_ref(c);
         self
     }
 
-    fn or_column_is_not_null_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrColumnIsNotNull(c));
+    fn or_column_is_not_null_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrColumnIsNotNull(c.column_name()));
         self
     }
 
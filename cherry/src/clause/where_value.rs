@@ -1,5 +1,6 @@
 use sqlx::{Database, Encode, Type};
 
+use crate::column::{AcceptValue, ColumnName};
 use crate::provider::Provider;
 use crate::sql::where_condition::Condition;
 
@@ -23,282 +24,346 @@ pub trait Where<'a, DB>: Provider<'a, DB> + Sized where DB: Database {
         self
     }
 
-    fn and_eq<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_eq<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.and_eq_ref(c, v);
         self
     }
 
-    fn and_eq_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_eq_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::AndEq(c));
+        self.sql_builder().add_where(Condition::AndEq(c.column_name()));
         self
     }
 
-    fn or_eq<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_eq<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.or_eq_ref(c, v);
         self
     }
 
-    fn or_eq_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_eq_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::OrEq(c));
+        self.sql_builder().add_where(Condition::OrEq(c.column_name()));
         self
     }
 
-    fn and_ge<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_ge<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.and_ge_ref(c, v);
         self
     }
 
-    fn and_ge_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_ge_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::AndGe(c));
+        self.sql_builder().add_where(Condition::AndGe(c.column_name()));
         self
     }
 
-    fn or_ge<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_ge<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.or_ge_ref(c, v);
         self
     }
 
-    fn or_ge_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_ge_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::OrGe(c));
+        self.sql_builder().add_where(Condition::OrGe(c.column_name()));
         self
     }
 
-    fn and_gt<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_gt<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.and_gt_ref(c, v);
         self
     }
 
-    fn and_gt_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_gt_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::AndGt(c));
+        self.sql_builder().add_where(Condition::AndGt(c.column_name()));
         self
     }
 
-    fn or_gt<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_gt<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.or_gt_ref(c, v);
         self
     }
 
-    fn or_gt_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_gt_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::OrGt(c));
+        self.sql_builder().add_where(Condition::OrGt(c.column_name()));
         self
     }
 
 
-    fn and_le<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_le<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.and_le_ref(c, v);
         self
     }
 
-    fn and_le_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_le_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::AndLe(c));
+        self.sql_builder().add_where(Condition::AndLe(c.column_name()));
         self
     }
 
-    fn or_le<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_le<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.or_le_ref(c, v);
         self
     }
 
-    fn or_le_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_le_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::OrLe(c));
+        self.sql_builder().add_where(Condition::OrLe(c.column_name()));
         self
     }
 
-    fn and_lt<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_lt<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.and_lt_ref(c, v);
         self
     }
 
-    fn and_lt_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn and_lt_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::AndLt(c));
+        self.sql_builder().add_where(Condition::AndLt(c.column_name()));
         self
     }
 
-    fn or_lt<V>(mut self, c: &'a str, v: V) -> Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_lt<C, V>(mut self, c: C, v: V) -> Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.or_lt_ref(c, v);
         self
     }
 
-    fn or_lt_ref<V>(&mut self, c: &'a str, v: V) -> &mut Self
-        where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+    fn or_lt_ref<C, V>(&mut self, c: C, v: V) -> &mut Self
+        where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
+            V: Encode<'a, DB> + Type<DB> + Send + 'a {
         self.add_value(v);
-        self.sql_builder().add_where(Condition::OrLt(c));
+        self.sql_builder().add_where(Condition::OrLt(c.column_name()));
         self
     }
 
-    fn and_is_null(mut self, c: &'a str) -> Self {
+    fn and_is_null<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_is_null_ref(c);
         self
     }
 
-    fn and_is_null_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndIsNull(c));
+    fn and_is_null_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndIsNull(c.column_name()));
         self
     }
 
-    fn or_is_null(mut self, c: &'a str) -> Self {
+    fn or_is_null<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_is_null_ref(c);
         self
     }
 
-    fn or_is_null_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrIsNull(c));
+    fn or_is_null_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrIsNull(c.column_name()));
         self
     }
 
-    fn and_is_not_null(mut self, c: &'a str) -> Self {
+    fn and_is_not_null<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_is_not_null_ref(c);
         self
     }
 
-    fn and_is_not_null_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndIsNotNull(c));
+    fn and_is_not_null_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndIsNotNull(c.column_name()));
         self
     }
 
-    fn or_is_not_null(mut self, c: &'a str) -> Self {
+    fn or_is_not_null<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_is_not_null_ref(c);
         self
     }
 
-    fn or_is_not_null_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrIsNotNull(c));
+    fn or_is_not_null_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrIsNotNull(c.column_name()));
         self
     }
 
-    fn and_between(mut self, c: &'a str) -> Self {
+    fn and_between<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_between_ref(c);
         self
     }
 
-    fn and_between_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndBetween(c));
+    fn and_between_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndBetween(c.column_name()));
         self
     }
 
-    fn or_between(mut self, c: &'a str) -> Self {
+    fn or_between<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_between_ref(c);
         self
     }
 
-    fn or_between_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrBetween(c));
+    fn or_between_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrBetween(c.column_name()));
         self
     }
 
-    fn and_not_between(mut self, c: &'a str) -> Self {
+    fn and_not_between<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.and_not_between_ref(c);
         self
     }
 
-    fn and_not_between_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::AndNotBetween(c));
+    fn and_not_between_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::AndNotBetween(c.column_name()));
         self
     }
 
-    fn or_not_between(mut self, c: &'a str) -> Self {
+    fn or_not_between<C>(mut self, c: C) -> Self
+        where C: ColumnName<'a, Self::Entity> {
         self.or_not_between_ref(c);
         self
     }
 
-    fn or_not_between_ref(&mut self, c: &'a str) -> &mut Self {
-        self.sql_builder().add_where(Condition::OrNotBetween(c));
+    fn or_not_between_ref<C>(&mut self, c: C) -> &mut Self
+        where C: ColumnName<'a, Self::Entity> {
+        self.sql_builder().add_where(Condition::OrNotBetween(c.column_name()));
         self
     }
 
-    fn and_in<V, I>(mut self, c: &'a str, v: I) -> Self
+    fn and_in<C, V, I>(mut self, c: C, v: I) -> Self
         where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
             V: Encode<'a, DB> + Type<DB> + Send + 'a,
             I: IntoIterator<Item = V> {
         self.and_in_ref(c, v);
         self
     }
 
-    fn and_in_ref<V, I>(&mut self, c: &'a str, v: I) -> &mut Self
+    fn and_in_ref<C, V, I>(&mut self, c: C, v: I) -> &mut Self
         where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
             V: Encode<'a, DB> + Type<DB> + Send + 'a,
             I: IntoIterator<Item = V> {
         let length = v.into_iter().map(|v| self.add_value(v)).count();
-        self.sql_builder().add_where(Condition::AndIn(c, length));
+        self.sql_builder().add_where(Condition::AndIn(c.column_name(), length));
         self
     }
 
-    fn or_in<V, I>(mut self, c: &'a str, v: I) -> Self
+    fn or_in<C, V, I>(mut self, c: C, v: I) -> Self
         where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
             V: Encode<'a, DB> + Type<DB> + Send + 'a,
             I: IntoIterator<Item = V> {
         self.or_in_ref(c, v);
         self
     }
 
-    fn or_in_ref<V, I>(&mut self, c: &'a str, v: I) -> &mut Self
+    fn or_in_ref<C, V, I>(&mut self, c: C, v: I) -> &mut Self
         where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
             V: Encode<'a, DB> + Type<DB> + Send + 'a,
             I: IntoIterator<Item = V> {
         let length = v.into_iter().map(|v| self.add_value(v)).count();
-        self.sql_builder().add_where(Condition::OrIn(c, length));
+        self.sql_builder().add_where(Condition::OrIn(c.column_name(), length));
         self
     }
 
-    fn and_not_in<V, I>(mut self, c: &'a str, v: I) -> Self
+    fn and_not_in<C, V, I>(mut self, c: C, v: I) -> Self
         where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
             V: Encode<'a, DB> + Type<DB> + Send + 'a,
             I: IntoIterator<Item = V> {
         self.and_not_in_ref(c, v);
         self
     }
 
-    fn and_not_in_ref<V, I>(&mut self, c: &'a str, v: I) -> &mut Self
+    fn and_not_in_ref<C, V, I>(&mut self, c: C, v: I) -> &mut Self
         where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
             V: Encode<'a, DB> + Type<DB> + Send + 'a,
             I: IntoIterator<Item = V> {
         let length = v.into_iter().map(|v| self.add_value(v)).count();
-        self.sql_builder().add_where(Condition::AndNotIn(c, length));
+        self.sql_builder().add_where(Condition::AndNotIn(c.column_name(), length));
         self
     }
 
-    fn or_not_in<V, I>(mut self, c: &'a str, v: I) -> Self
+    fn or_not_in<C, V, I>(mut self, c: C, v: I) -> Self
         where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
             V: Encode<'a, DB> + Type<DB> + Send + 'a,
             I: IntoIterator<Item = V> {
         self.or_not_in_ref(c, v);
         self
     }
 
-    fn or_not_in_ref<V, I>(&mut self, c: &'a str, v: I) -> &mut Self
+    fn or_not_in_ref<C, V, I>(&mut self, c: C, v: I) -> &mut Self
         where
+            C: ColumnName<'a, Self::Entity> + AcceptValue<V>,
             V: Encode<'a, DB> + Type<DB> + Send + 'a,
             I: IntoIterator<Item = V> {
         let length = v.into_iter().map(|v| self.add_value(v)).count();
-        self.sql_builder().add_where(Condition::OrNotIn(c, length));
+        self.sql_builder().add_where(Condition::OrNotIn(c.column_name(), length));
         self
     }
 
@@ -307,11 +372,6 @@ pub trait Where<'a, DB>: Provider<'a, DB> + Sized where DB: Database {
 
 #[cfg(test)]
 mod tests {
-    use std::marker::PhantomData;
-
-    use crate::sqlx::Sqlite;
-
-    use super::*;
 
     #[test]
     fn test() {
@@ -0,0 +1,83 @@
+use std::marker::PhantomData;
+
+// A column of the entity `T` holding values of type `F`, generated by the derive:
+// `User::NAME: Column<User, String>`. `K` is `KeyColumn` for the primary key columns,
+// `User::ID: Column<User, u32, KeyColumn>`, and `ManagedColumn` for the generated columns and the
+// ones managed by cherry. `Tracked::set()` accepts neither.
+pub struct Column<T, F, K = ()> {
+    name: &'static str,
+    field: fn(&mut T) -> &mut F,
+    _a: PhantomData<fn() -> (T, F)>,
+    _k: PhantomData<fn() -> K>,
+}
+
+// The marker of the primary key columns.
+pub enum KeyColumn {}
+
+// The marker of the columns the updates of an instance never set: generated, version, timestamps
+// and tenant.
+pub enum ManagedColumn {}
+
+impl<T, F, K> Column<T, F, K> {
+
+    pub const fn new(name: &'static str, field: fn(&mut T) -> &mut F) -> Self {
+        Self { name, field, _a: PhantomData, _k: PhantomData }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    // the field of the entity
+    pub fn field_mut(self, t: &mut T) -> &mut F {
+        (self.field)(t)
+    }
+}
+
+impl<T, F, K> Clone for Column<T, F, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, F, K> Copy for Column<T, F, K> {}
+
+// Accepted where a column name of the entity `T` is expected, a plain `&str` or a typed `Column`
+// of `T`.
+pub trait ColumnName<'a, T> {
+    fn column_name(self) -> &'a str;
+}
+
+impl<'a, T> ColumnName<'a, T> for &'a str {
+    fn column_name(self) -> &'a str {
+        self
+    }
+}
+
+impl<'a, T, F, K> ColumnName<'a, T> for Column<T, F, K> {
+    fn column_name(self) -> &'a str {
+        self.name
+    }
+}
+
+// The column accepts values of type `V`. Anything goes for a plain `&str`.
+pub trait AcceptValue<V> {}
+
+impl<V> AcceptValue<V> for &str {}
+
+impl<T, F, K, V> AcceptValue<V> for Column<T, F, K> where V: ColumnValue<F> {}
+
+// `Self` can be bound in place of a value of the field type `F`.
+pub trait ColumnValue<F> {}
+
+impl<F> ColumnValue<F> for F {}
+
+impl<F> ColumnValue<F> for &F {}
+
+impl<F> ColumnValue<Option<F>> for F {}
+
+impl<F> ColumnValue<Option<F>> for &F {}
+
+impl ColumnValue<String> for &str {}
+
+impl ColumnValue<Option<String>> for &str {}
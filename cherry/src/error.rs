@@ -0,0 +1,38 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+// Errors raised by cherry itself, recover them with `cherry::Error::downcast_ref::<QueryError>()`.
+#[derive(Debug)]
+pub enum QueryError {
+    Timeout(Duration),
+    TooManyParameters(usize),
+    NotFound(&'static str), // table
+    StaleObject(&'static str), // table, the version changed since loaded
+    MissingTenant(&'static str), // table, queried out of a `TenantId` scope
+    TenantMismatch(&'static str), // table, an insert row of another tenant than the scope's
+    VersionedSave(&'static str), // table, `save()` of a versioned entity with MySQL
+    DuplicateKey(&'static str), // table, `reload_all()` of entities sharing a key
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::Timeout(d) => write!(f, "Query timed out after {:?}", d),
+            QueryError::TooManyParameters(n) =>
+                write!(f, "Query binds {} parameters, more than the database allows", n),
+            QueryError::NotFound(table) => write!(f, "No row found in `{}`", table),
+            QueryError::StaleObject(table) =>
+                write!(f, "The row of `{}` was changed or deleted since loaded", table),
+            QueryError::MissingTenant(table) =>
+                write!(f, "`{}` is queried without a tenant, use `TenantId::scope` or `unscoped()`", table),
+            QueryError::TenantMismatch(table) =>
+                write!(f, "A row of `{}` is inserted with another tenant than the one of `TenantId::scope`", table),
+            QueryError::VersionedSave(table) =>
+                write!(f, "`{}` has a version, MySQL can't save it, use `insert()` or `update_by_pk()`", table),
+            QueryError::DuplicateKey(table) =>
+                write!(f, "The same row of `{}` is given twice, reload a single entity per key", table),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
@@ -1,17 +1,37 @@
+use std::future::Future;
+use std::time::Duration;
+
 use anyhow::Error;
 use futures_core::future::BoxFuture;
-use sqlx::{Database, Executor, FromRow};
+use sqlx::{Acquire, Database, Decode, Executor, FromRow, Row, Type};
 
 use crate::Cherry;
+use crate::error::QueryError;
+use crate::explain::QueryPlan;
 use crate::query::Query;
+use crate::sql::TargetDatabase;
 
 pub trait QueryExecutor<'a, T, DB> where T: Cherry<'a, DB>, DB: Database {
 
+    // Fails with `QueryError::TooManyParameters` if the insert rows are over the parameter limit
+    // of the database, split them with `execute_chunked()`.
     fn execute<'e, E>(self, e: E) -> BoxFuture<'e, Result<DB::QueryResult, Error>>
         where
             'a: 'e,
             E: Executor<'e, Database = DB> + 'e;
 
+    // Split the insert rows into statements under the parameter limit of the database, run in
+    // sequence on one connection, the rows affected are summed up. The timeout is for all of them.
+    fn execute_chunked<'e, A>(self, a: A) -> BoxFuture<'e, Result<DB::QueryResult, Error>>
+        where
+            'a: 'e,
+            A: Acquire<'e, Database = DB> + Send + 'e;
+
+    // Same as `execute_chunked`, all chunks are committed or none.
+    fn execute_chunked_tx<'e, A>(self, a: A) -> BoxFuture<'e, Result<DB::QueryResult, Error>>
+        where
+            'a: 'e,
+            A: Acquire<'e, Database = DB> + Send + 'e;
 
     fn one<'e, E>(self, e: E) -> BoxFuture<'e, Result<Option<T>, Error>>
         where
@@ -23,6 +43,14 @@ pub trait QueryExecutor<'a, T, DB> where T: Cherry<'a, DB>, DB: Database {
             'a: 'e,
             E: Executor<'e, Database = DB> + 'e;
 
+    // The rows with the value of `column` decoded aside, to group them by foreign key.
+    #[doc(hidden)]
+    fn all_keyed<'e, K, E>(self, column: &'static str, e: E) -> BoxFuture<'e, Result<Vec<(K, T)>, Error>>
+        where
+            'a: 'e,
+            K: for<'r> Decode<'r, DB> + Type<DB> + Send + 'e,
+            E: Executor<'e, Database = DB> + 'e;
+
     // implement FromRow for tuples of types that implement Decode
     // up to tuples of 16 values
     fn tuple<'e, O, E>(self, e: E) -> BoxFuture<'e, Result<Option<O>, Error>>
@@ -37,6 +65,17 @@ pub trait QueryExecutor<'a, T, DB> where T: Cherry<'a, DB>, DB: Database {
             O: Send + Unpin + for<'r> FromRow<'r, DB::Row> + 'e,
             E: Executor<'e, Database = DB> + 'e;
 
+    fn explain<'e, E>(self, e: E) -> BoxFuture<'e, Result<QueryPlan, Error>>
+        where
+            'a: 'e,
+            E: Executor<'e, Database = DB> + 'e;
+
+    // The query is actually executed, wrap it in a transaction if it modifies data.
+    fn explain_analyze<'e, E>(self, e: E) -> BoxFuture<'e, Result<QueryPlan, Error>>
+        where
+            'a: 'e,
+            E: Executor<'e, Database = DB> + 'e;
+
 }
 
 
@@ -47,25 +86,75 @@ impl<'a, T> QueryExecutor<'a, T, $db> for Query<'a, T, $db>
     where
         T: Cherry<'a, $db> {
 
-    fn execute<'e, E>(self, e: E)
+    fn execute<'e, E>(mut self, e: E)
                       -> BoxFuture<'e, Result<<$db as Database>::QueryResult, Error>>
         where
             'a: 'e,
             E: Executor<'e, Database=$db> + 'e {
         Box::pin(async move {
+            if self.is_empty() {
+                self.succeeded();
+                return Ok(Default::default());
+            }
+            self.check_parameters()?;
             let sql = self.sql_builder.as_sql();
-            Ok(sqlx::query_with(&sql, self.arguments).execute(e).await?)
+            let query = sqlx::query_with(&sql, self.take_arguments()?).execute(e);
+            let result = with_timeout(self.timeout, query).await?;
+            if self.check_stale && result.rows_affected() == 0 {
+                return Err(QueryError::StaleObject(T::table()).into());
+            }
+            self.succeeded();
+            Ok(result)
         })
     }
 
-    fn one<'e, E>(self, e: E) -> BoxFuture<'e, Result<Option<T>, Error>>
+    fn execute_chunked<'e, A>(mut self, a: A)
+                              -> BoxFuture<'e, Result<<$db as Database>::QueryResult, Error>>
+        where
+            'a: 'e,
+            A: Acquire<'e, Database=$db> + Send + 'e {
+        Box::pin(async move {
+            if self.is_empty() {
+                self.succeeded();
+                return Ok(Default::default());
+            }
+            let mut conn = a.acquire().await?;
+            let result = self.execute_chunks(&mut *conn).await?;
+            self.succeeded();
+            Ok(result)
+        })
+    }
+
+    fn execute_chunked_tx<'e, A>(mut self, a: A)
+                                 -> BoxFuture<'e, Result<<$db as Database>::QueryResult, Error>>
+        where
+            'a: 'e,
+            A: Acquire<'e, Database=$db> + Send + 'e {
+        Box::pin(async move {
+            if self.is_empty() {
+                self.succeeded();
+                return Ok(Default::default());
+            }
+            let mut tx = a.begin().await?;
+            // the server cancels the chunks too, where the transaction can have a timeout
+            if let Some(sql) = self.timeout.and_then(|t| self.sql_builder.db.local_statement_timeout(t)) {
+                sqlx::query(&sql).execute(&mut *tx).await?;
+            }
+            let result = self.execute_chunks(&mut *tx).await?;
+            tx.commit().await?;
+            self.succeeded();
+            Ok(result)
+        })
+    }
+
+    fn one<'e, E>(mut self, e: E) -> BoxFuture<'e, Result<Option<T>, Error>>
         where
             'a: 'e,
             E: Executor<'e, Database=$db> + 'e {
         Box::pin(async move {
             let sql = self.sql_builder.as_sql();
-            let row = sqlx::query_with(&sql, self.arguments)
-                .fetch_optional(e).await?;
+            let query = sqlx::query_with(&sql, self.take_arguments()?).fetch_optional(e);
+            let row = with_timeout(self.timeout, query).await?;
             let t = match row {
                 Some(row) => Some(T::from_row(&row)?),
                 _ => None,
@@ -74,15 +163,15 @@ impl<'a, T> QueryExecutor<'a, T, $db> for Query<'a, T, $db>
         })
     }
 
-    fn all<'e, E>(self, e: E) -> BoxFuture<'e, Result<Vec<T>, Error>>
+    fn all<'e, E>(mut self, e: E) -> BoxFuture<'e, Result<Vec<T>, Error>>
         where
             'a: 'e,
             E: Executor<'e, Database=$db> + 'e {
 
         Box::pin(async move {
             let sql = self.sql_builder.as_sql();
-            let rows = sqlx::query_with(&sql, self.arguments)
-                .fetch_all(e).await?;
+            let query = sqlx::query_with(&sql, self.take_arguments()?).fetch_all(e);
+            let rows = with_timeout(self.timeout, query).await?;
             let mut vec = Vec::with_capacity(rows.len());
             for row in rows {
                 vec.push(T::from_row(&row)?);
@@ -91,7 +180,25 @@ impl<'a, T> QueryExecutor<'a, T, $db> for Query<'a, T, $db>
         })
     }
 
-    fn tuple<'e, O, E>(self, e: E) -> BoxFuture<'e, Result<Option<O>, Error>>
+    fn all_keyed<'e, K, E>(mut self, column: &'static str, e: E) -> BoxFuture<'e, Result<Vec<(K, T)>, Error>>
+        where
+            'a: 'e,
+            K: for<'r> Decode<'r, $db> + Type<$db> + Send + 'e,
+            E: Executor<'e, Database=$db> + 'e {
+
+        Box::pin(async move {
+            let sql = self.sql_builder.as_sql();
+            let query = sqlx::query_with(&sql, self.take_arguments()?).fetch_all(e);
+            let rows = with_timeout(self.timeout, query).await?;
+            let mut vec = Vec::with_capacity(rows.len());
+            for row in rows {
+                vec.push((row.try_get::<K, _>(column)?, T::from_row(&row)?));
+            }
+            Ok(vec)
+        })
+    }
+
+    fn tuple<'e, O, E>(mut self, e: E) -> BoxFuture<'e, Result<Option<O>, Error>>
         where
             'a: 'e,
             O: Send + Unpin + for<'r> FromRow<'r, <$db as Database>::Row> + 'e,
@@ -99,8 +206,8 @@ impl<'a, T> QueryExecutor<'a, T, $db> for Query<'a, T, $db>
 
         Box::pin(async move {
             let sql = self.sql_builder.as_sql();
-            let row = sqlx::query_with(&sql, self.arguments)
-                .fetch_optional(e).await?;
+            let query = sqlx::query_with(&sql, self.take_arguments()?).fetch_optional(e);
+            let row = with_timeout(self.timeout, query).await?;
             let tuple = match row {
                 Some(row) => O::from_row(&row).map(Some),
                 _ => Ok(None),
@@ -109,7 +216,7 @@ impl<'a, T> QueryExecutor<'a, T, $db> for Query<'a, T, $db>
         })
     }
 
-    fn tuples<'e, O, E>(self, e: E) -> BoxFuture<'e, Result<Vec<O>, Error>>
+    fn tuples<'e, O, E>(mut self, e: E) -> BoxFuture<'e, Result<Vec<O>, Error>>
         where
             'a: 'e,
             O: Send + Unpin + for<'r> FromRow<'r, <$db as Database>::Row> + 'e,
@@ -117,8 +224,8 @@ impl<'a, T> QueryExecutor<'a, T, $db> for Query<'a, T, $db>
 
         Box::pin(async move {
             let sql = self.sql_builder.as_sql();
-            let rows = sqlx::query_with(&sql, self.arguments)
-                .fetch_all(e).await?;
+            let query = sqlx::query_with(&sql, self.take_arguments()?).fetch_all(e);
+            let rows = with_timeout(self.timeout, query).await?;
 
             let mut vec = Vec::with_capacity(rows.len());
             for row in rows {
@@ -129,12 +236,110 @@ impl<'a, T> QueryExecutor<'a, T, $db> for Query<'a, T, $db>
         })
     }
 
+    fn explain<'e, E>(self, e: E) -> BoxFuture<'e, Result<QueryPlan, Error>>
+        where
+            'a: 'e,
+            E: Executor<'e, Database=$db> + 'e {
+        self.query_plan(e, false)
+    }
+
+    fn explain_analyze<'e, E>(self, e: E) -> BoxFuture<'e, Result<QueryPlan, Error>>
+        where
+            'a: 'e,
+            E: Executor<'e, Database=$db> + 'e {
+        self.query_plan(e, true)
+    }
+
+}
+
+impl<'a, T> Query<'a, T, $db>
+    where
+        T: Cherry<'a, $db> {
+
+    async fn execute_chunks(&mut self, conn: &mut <$db as Database>::Connection)
+                            -> Result<<$db as Database>::QueryResult, Error> {
+        let statements = self.take_statements()?;
+        let chunks = async move {
+            let mut result = <$db as Database>::QueryResult::default();
+            for (sql, arguments) in statements {
+                result.extend(Some(sqlx::query_with(&sql, arguments).execute(&mut *conn).await?));
+            }
+            Ok::<_, sqlx::Error>(result)
+        };
+        let result = with_timeout(self.timeout, chunks).await?;
+        if self.check_stale && result.rows_affected() == 0 {
+            return Err(QueryError::StaleObject(T::table()).into());
+        }
+        Ok(result)
+    }
+
+    fn query_plan<'e, E>(mut self, e: E, analyze: bool) -> BoxFuture<'e, Result<QueryPlan, Error>>
+        where
+            'a: 'e,
+            E: Executor<'e, Database=$db> + 'e {
+
+        Box::pin(async move {
+            let db = self.sql_builder.db;
+            let sql = self.sql_builder.as_explain_sql(analyze);
+            let query = sqlx::query_with(&sql, self.take_arguments()?).fetch_all(e);
+            let rows = with_timeout(self.timeout, query).await?;
+
+            let mut vec = Vec::with_capacity(rows.len());
+            for row in rows {
+                vec.push(match db {
+                    TargetDatabase::Sqlite => row.try_get::<String, _>("detail")?,
+                    // The json plan column is plain text on the wire.
+                    _ => row.try_get_unchecked::<String, _>(0)?,
+                });
+            }
+
+            Ok(QueryPlan::new(db, vec))
+        })
+    }
 
 }
 
     };
 }
 
+async fn with_timeout<F, O>(timeout: Option<Duration>, f: F) -> Result<O, Error>
+    where F: Future<Output = Result<O, sqlx::Error>> {
+    match timeout {
+        #[cfg(any(feature = "async-std", feature = "tokio"))]
+        Some(duration) => Ok(runtime_timeout(duration, f).await??),
+        _ => Ok(f.await?),
+    }
+}
+
+// Use the timer of the running runtime, prefer tokio when both are enabled like sqlx does.
+#[cfg(any(feature = "async-std", feature = "tokio"))]
+async fn runtime_timeout<F: Future>(duration: Duration, f: F) -> Result<F::Output, QueryError> {
+    #[cfg(all(feature = "tokio", feature = "async-std"))]
+    if tokio::runtime::Handle::try_current().is_err() {
+        return async_std::future::timeout(duration, f).await
+            .map_err(|_| QueryError::Timeout(duration));
+    }
+
+    #[cfg(feature = "tokio")]
+    let result = tokio::time::timeout(duration, f).await;
+    #[cfg(not(feature = "tokio"))]
+    let result = async_std::future::timeout(duration, f).await;
+
+    result.map_err(|_| QueryError::Timeout(duration))
+}
+
+// Let the server cancel statements running longer than `timeout` too, until the end of the
+// current transaction. Outside of a transaction it has no effect. Every query of the transaction
+// runs under it, set it once after `begin()`.
+#[cfg(feature = "postgres")]
+pub async fn set_local_statement_timeout(conn: &mut sqlx::PgConnection, timeout: Duration)
+    -> Result<(), Error> {
+    if let Some(sql) = TargetDatabase::Postgres.local_statement_timeout(timeout) {
+        sqlx::query(&sql).execute(conn).await?;
+    }
+    Ok(())
+}
+
 #[cfg(feature = "sqlite")]
 gen_executor!(sqlx::Sqlite);
 #[cfg(feature = "postgres")]
@@ -0,0 +1,100 @@
+use crate::sql::TargetDatabase;
+
+/// The plan reported by the database for a query.
+///
+/// What the rows hold depends on the database:
+/// - SQLite: one row per plan step, the `detail` column of `EXPLAIN QUERY PLAN`.
+/// - PostgreSQL: a single JSON document, with or without `ANALYZE`.
+/// - MySQL: a single JSON document with `EXPLAIN FORMAT=JSON`, the text tree printed by
+///   `EXPLAIN ANALYZE` otherwise.
+#[derive(Debug, Clone)]
+pub struct QueryPlan {
+    db: TargetDatabase,
+    rows: Vec<String>,
+}
+
+impl QueryPlan {
+
+    pub(crate) fn new(db: TargetDatabase, rows: Vec<String>) -> Self {
+        Self { db, rows }
+    }
+
+    pub fn rows(&self) -> &[String] {
+        &self.rows
+    }
+
+    /// Whether any step of the plan reads through an index (primary key included).
+    pub fn uses_index(&self) -> bool {
+        self.rows.iter().any(|row| match self.db {
+            TargetDatabase::Sqlite => row.contains(" USING INDEX ")
+                || row.contains(" USING COVERING INDEX ")
+                || row.contains(" USING PRIMARY KEY")
+                || row.contains(" USING INTEGER PRIMARY KEY"),
+            TargetDatabase::Postgres => row.contains(r#""Index Name""#),
+            // `EXPLAIN FORMAT=JSON` reports the chosen key, `EXPLAIN ANALYZE` prints a tree.
+            TargetDatabase::MySql => row.contains(r#""key":"#)
+                || row.contains("Index lookup")
+                || row.contains("Index range scan")
+                || row.contains("Covering index"),
+        })
+    }
+
+    /// Whether any step of the plan reads through the index with the given name.
+    pub fn uses_index_named(&self, index: &str) -> bool {
+        self.rows.iter().any(|row| match self.db {
+            TargetDatabase::Sqlite => row.contains(&format!(" INDEX {} ", index))
+                || row.ends_with(&format!(" INDEX {}", index)),
+            TargetDatabase::Postgres => row.contains(&format!(r#""Index Name": "{}""#, index)),
+            TargetDatabase::MySql => row.contains(&format!(r#""key": "{}""#, index))
+                || row.contains(&format!(" using {} ", index))
+                || row.contains(&format!(" using {})", index)),
+        })
+    }
+
+    /// Panics with the full plan if the query does not use the index with the given name.
+    /// Pass `None` to accept any index.
+    pub fn assert_uses_index(&self, index: Option<&str>) {
+        let used = match index {
+            Some(index) => self.uses_index_named(index),
+            _ => self.uses_index(),
+        };
+        assert!(used, "Expected the query to use index `{}`, got plan:\n{}",
+                index.unwrap_or("*"), self.rows.join("\n"));
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_plan() {
+        let plan = QueryPlan::new(TargetDatabase::Sqlite, vec![
+            "SEARCH user USING INDEX idx_user_name (name=?)".into(),
+        ]);
+        assert!(plan.uses_index());
+        assert!(plan.uses_index_named("idx_user_name"));
+        assert!(!plan.uses_index_named("idx_user"));
+
+        let plan = QueryPlan::new(TargetDatabase::Sqlite, vec!["SCAN user".into()]);
+        assert!(!plan.uses_index());
+    }
+
+    #[test]
+    fn test_postgres_plan() {
+        let plan = QueryPlan::new(TargetDatabase::Postgres, vec![
+            r#"[{"Plan": {"Node Type": "Index Scan", "Index Name": "user_pkey"}}]"#.into(),
+        ]);
+        plan.assert_uses_index(Some("user_pkey"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_uses_index() {
+        let plan = QueryPlan::new(TargetDatabase::MySql, vec![
+            r#"{"query_block": {"table": {"access_type": "ALL"}}}"#.into(),
+        ]);
+        plan.assert_uses_index(None);
+    }
+}
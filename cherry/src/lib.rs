@@ -2,20 +2,41 @@
 
 pub use {
     anyhow::Error,
-    cherry::Cherry,
-    cherry_derive::Cherry,
+    cherry::{Cherry, InsertValues, TenantCheck},
+    cherry::prefix_columns,
+    column::{AcceptValue, Column, ColumnName, ColumnValue, KeyColumn, ManagedColumn},
+    cherry_derive::{Cherry, CherryEnum},
+    error::QueryError,
     executor::QueryExecutor,
+    explain::QueryPlan,
+    primary_key::{PrimaryKey, SingleKey},
     query::Query,
+    relation::{HasMany, ManyToMany},
+    tenant::{Scoped, TenantId},
+    timestamp::Timestamp,
+    tracked::Tracked,
     crate::sqlx::pool::Pool,
     crate::sqlx::pool::PoolOptions,
 };
 
 pub mod clause;
 pub(crate) mod cherry;
+pub(crate) mod column;
+pub(crate) mod error;
 pub(crate) mod query;
 pub(crate) mod executor;
+pub(crate) mod explain;
+pub(crate) mod primary_key;
 pub(crate) mod provider;
+pub(crate) mod relation;
 pub(crate) mod sql;
+pub(crate) mod tenant;
+pub(crate) mod timestamp;
+pub(crate) mod tracked;
+#[cfg(feature = "postgres")]
+pub(crate) mod unnest;
+#[doc(hidden)]
+pub mod verify;
 
 #[cfg(feature = "sqlite")]
 pub mod sqlite {
@@ -25,6 +46,8 @@ pub mod sqlite {
 #[cfg(feature = "postgres")]
 pub mod postgres {
     pub use sqlx::postgres::{Postgres, PgPool, PgPoolOptions};
+    pub use crate::executor::set_local_statement_timeout;
+    pub use crate::unnest::{Unnest, UnnestArray};
 }
 
 #[cfg(feature = "mysql")]
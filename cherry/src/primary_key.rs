@@ -0,0 +1,189 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use anyhow::Error;
+use futures_core::future::BoxFuture;
+use sqlx::{Acquire, Database, Decode, Encode, Executor, Type};
+use sqlx::database::HasArguments;
+
+use crate::{Cherry, HasMany, QueryError, QueryExecutor};
+use crate::clause::Where;
+use crate::query::Query;
+use crate::sql::TargetDatabase;
+
+type Loading<'e, T> = BoxFuture<'e, Result<T, Error>>;
+
+// Implemented by the derive for entities declaring `#[cherry(primary_key)]` fields.
+pub trait PrimaryKey<'a, DB>: Cherry<'a, DB> + 'a where DB: Database {
+
+    // the type of the key field, a tuple of the fields for composite keys
+    type Key: Send + 'a;
+
+    // bind the key values, in the order of `primary_key()`
+    fn key_arguments(key: Self::Key, arguments: &mut <DB as HasArguments<'a>>::Arguments);
+
+    fn key(&self) -> Self::Key;
+
+    // Bind the values set by an update of `self`, `columns` or every `update_columns()`, then the
+    // key and the version. Returns the increment of the version of `self`, run once the row is
+    // updated. The derive generates it for the versioned entities, borrowing their fields apart.
+    fn update_by_key_arguments(&'a mut self, columns: Option<&[&str]>,
+                               arguments: &mut <DB as HasArguments<'a>>::Arguments)
+                               -> Option<Box<dyn FnOnce() + Send + 'a>> {
+        let this: &'a Self = self;
+        match columns {
+            Some(columns) => columns.iter().for_each(|c| {
+                this.column_arguments(c, arguments);
+            }),
+            _ => this.update_arguments(arguments),
+        }
+        this.primary_key_arguments(arguments);
+        if let Some(c) = Self::version() {
+            this.column_arguments(c, arguments);
+        }
+        None
+    }
+
+    fn find(key: Self::Key) -> Query<'a, Self, DB> {
+        Query::new_find(key)
+    }
+
+    // One `IN` query, `("a", "b") IN ((?, ?), (?, ?))` for composite keys.
+    fn find_many<I>(keys: I) -> Query<'a, Self, DB>
+        where I: IntoIterator<Item = Self::Key> {
+        Query::new_find_many(keys)
+    }
+
+    // UPDATE t SET every `update_columns()` WHERE key = ?
+    // With a version, `AND version = ?`, and the version incremented in the row and in `self` once
+    // it's executed without error.
+    fn update_by_pk(&'a mut self) -> Query<'a, Self, DB> {
+        Query::new_update_by_pk(self)
+    }
+
+    fn delete_by_pk(&'a self) -> Query<'a, Self, DB> {
+        Query::new_delete_by_pk(self)
+    }
+
+    // Insert, or update the other columns when the key exists. The key must be inserted, not
+    // generated by the database. `QueryError::StaleObject` if the version of the existing row
+    // changed, `QueryError::VersionedSave` with MySQL for a versioned entity. The `created_at` and
+    // soft delete columns of an existing row are kept.
+    fn save(&'a self) -> Query<'a, Self, DB> {
+        Query::new_save(self)
+    }
+
+    // Fail with `QueryError::NotFound` if there is no such row.
+    fn find_or_fail<'e, E>(key: Self::Key, e: E) -> BoxFuture<'e, Result<Self, Error>>
+        where
+            'a: 'e,
+            E: Executor<'e, Database = DB> + 'e,
+            Query<'a, Self, DB>: QueryExecutor<'a, Self, DB> {
+        let one = Self::find(key).one(e);
+        Box::pin(async move {
+            one.await?.ok_or_else(|| QueryError::NotFound(Self::table()).into())
+        })
+    }
+
+    // Select the row again and overwrite `self`, `QueryError::NotFound` if it's gone.
+    fn reload<'e, E>(&'e mut self, e: E) -> BoxFuture<'e, Result<(), Error>>
+        where
+            'a: 'e,
+            E: Executor<'e, Database = DB> + 'e,
+            Query<'a, Self, DB>: QueryExecutor<'a, Self, DB> {
+        let fresh = Self::find_or_fail(self.key(), e);
+        Box::pin(async move {
+            *self = fresh.await?;
+            Ok(())
+        })
+    }
+
+    // Reload every row with one `IN` query. Nothing is overwritten if a row is gone, nor queried
+    // if two entities have the same key, `QueryError::DuplicateKey`: entities are not `Clone`.
+    fn reload_all<'e, E>(v: &'e mut [Self], e: E) -> BoxFuture<'e, Result<(), Error>>
+        where
+            'a: 'e,
+            E: Executor<'e, Database = DB> + 'e,
+            Self::Key: Eq + Hash,
+            Query<'a, Self, DB>: QueryExecutor<'a, Self, DB> {
+        let mut keys = HashSet::new();
+        if !v.iter().all(|row| keys.insert(row.key())) {
+            return Box::pin(async { Err(QueryError::DuplicateKey(Self::table()).into()) });
+        }
+        let all = Self::find_many(keys).all(e);
+        Box::pin(async move {
+            let mut fresh = all.await?.into_iter()
+                .map(|row| (row.key(), row))
+                .collect::<HashMap<Self::Key, Self>>();
+            if v.iter().any(|row| !fresh.contains_key(&row.key())) {
+                return Err(QueryError::NotFound(Self::table()).into());
+            }
+            v.iter_mut().for_each(|row| if let Some(fresh) = fresh.remove(&row.key()) {
+                *row = fresh;
+            });
+            Ok(())
+        })
+    }
+
+    // The `R` of every entity with one `IN` query, by key, on one connection. The keys are split
+    // into as many queries as needed under the parameter limit. A single level is loaded, the
+    // nested relations take one more call per level, with the related entities of the earlier one:
+    //
+    // let novels = Author::with_related::<Novel>(authors, &pool).await?;
+    // let chapters = Novel::load_related::<Chapter>(novels.iter().flat_map(|(_, v)| v), &pool).await?;
+    fn load_related<'e, 'p, R>(parents: impl IntoIterator<Item = &'p Self>, a: impl Acquire<'e, Database = DB> + Send + 'e)
+                               -> Loading<'e, HashMap<Self::Key, Vec<R>>>
+        where
+            'a: 'e,
+            Self: HasMany<'a, R, DB> + 'p,
+            Self::Key: Eq + Hash + Encode<'a, DB> + Type<DB> + for<'r> Decode<'r, DB>,
+            R: Cherry<'a, DB> + 'a,
+            for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+            Query<'a, R, DB>: QueryExecutor<'a, R, DB> {
+        let mut keys = parents.into_iter().map(|parent| parent.key()).collect::<Vec<Self::Key>>();
+        // one value left for the tenant
+        let size = TargetDatabase::new::<DB>().max_parameters() - 1;
+        let mut selects = vec![];
+        while !keys.is_empty() {
+            let rest = keys.split_off(size.min(keys.len()));
+            selects.push(R::select().and_in(Self::foreign_key(), keys));
+            keys = rest;
+        }
+        Box::pin(async move {
+            let mut related = HashMap::<Self::Key, Vec<R>>::new();
+            if selects.is_empty() {
+                return Ok(related);
+            }
+            let mut conn = a.acquire().await?;
+            for select in selects {
+                let rows = select.all_keyed::<Self::Key, _>(Self::foreign_key(), &mut *conn).await?;
+                rows.into_iter().for_each(|(key, row)| related.entry(key).or_default().push(row));
+            }
+            Ok(related)
+        })
+    }
+
+    // Every entity with its `R`, in the same order. The entities sharing a key get a copy each.
+    fn with_related<'e, R>(parents: Vec<Self>, a: impl Acquire<'e, Database = DB> + Send + 'e)
+                           -> Loading<'e, Vec<(Self, Vec<R>)>>
+        where
+            'a: 'e,
+            Self: HasMany<'a, R, DB>,
+            Self::Key: Eq + Hash + Encode<'a, DB> + Type<DB> + for<'r> Decode<'r, DB>,
+            R: Cherry<'a, DB> + Clone + 'a,
+            for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+            Query<'a, R, DB>: QueryExecutor<'a, R, DB> {
+        let related = Self::load_related::<R>(&parents, a);
+        Box::pin(async move {
+            let related = related.await?;
+            Ok(parents.into_iter().map(|parent| {
+                let rows = related.get(&parent.key()).cloned().unwrap_or_default();
+                (parent, rows)
+            }).collect())
+        })
+    }
+}
+
+// Implemented by the derive for the entities with a single `#[cherry(primary_key)]` field, the
+// ones a single column can reference, like the columns of a join table.
+pub trait SingleKey<'a, DB>: PrimaryKey<'a, DB> where DB: Database {}
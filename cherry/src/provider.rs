@@ -4,8 +4,14 @@ use crate::sql::builder::SqlBuilder;
 
 pub trait Provider<'a, DB>: Sized where DB: Database {
 
+    // the entity queried, the typed columns of another entity are not accepted
+    type Entity;
+
     fn add_value<V>(&mut self, v: V) where V: Encode<'a, DB> + Type<DB> + Send + 'a;
 
+    // bound last, after the values of the scopes
+    fn add_end_value<V>(&mut self, v: V) where V: Encode<'a, DB> + Type<DB> + Send + 'a;
+
     fn sql_builder(&mut self) -> &mut SqlBuilder<'a>;
 
 }
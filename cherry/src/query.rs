@@ -1,19 +1,32 @@
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use sqlx::{Arguments, Database, Encode, Type};
 use sqlx::database::HasArguments;
 
-use crate::Cherry;
+use crate::{Cherry, Error, InsertValues, ManyToMany, PrimaryKey, QueryError, SingleKey, TenantCheck};
 use crate::clause::{End, InsertConflict, UpdateSet, Where, WhereColumn};
 use crate::clause::select_column::SelectColumn;
 use crate::provider::Provider;
 use crate::sql::builder::SqlBuilder;
-use crate::sql::TargetDatabase;
+use crate::sql::{InsertOnConflict, SqlType, TargetDatabase};
+use crate::sql::update_set_clause::UpdateSetSection;
+use crate::sql::where_condition::Condition;
+
+type EndValue<'a, DB> = Box<dyn FnOnce(&mut <DB as HasArguments<'a>>::Arguments) + Send + 'a>;
 
 pub struct Query<'a, T, DB: Database> {
     pub(crate) arguments: <DB as HasArguments<'a>>::Arguments,
     pub(crate) sql_builder: SqlBuilder<'a>,
-    _a: PhantomData<T>,
+    pub(crate) timeout: Option<Duration>,
+    rows: Vec<InsertValues<'a, DB>>, // insert rows, bound when executed, split into chunks if needed
+    tenants: Vec<TenantCheck<'a>>, // the tenant of every insert row, checked when executed
+    pub(crate) values: usize, // count of values bound besides the insert rows
+    pub(crate) check_stale: bool, // `QueryError::StaleObject` if no row is affected
+    pub(crate) after_success: Option<Box<dyn FnOnce() + Send + 'a>>, // run once executed without error
+    end_values: Vec<EndValue<'a, DB>>, // LIMIT and OFFSET, or the upsert values, bound last
+    scoped: bool, // false with `unscoped()`
+    _a: PhantomData<fn() -> &'a T>, // implies `T: 'a` like `&'a T`, but is `Send` without `T: Sync`
 }
 
 impl<'a, T, DB> Query<'a, T, DB>
@@ -22,54 +35,495 @@ impl<'a, T, DB> Query<'a, T, DB>
         DB: Database {
 
     pub(crate) fn new_insert(v: &'a T) -> Self {
-        let mut arguments = <DB as HasArguments<'a>>::Arguments::default();
-        v.arguments(&mut arguments);
-        Self::create_insert(arguments, 1)
-    }
-
-    pub(crate) fn new_insert_bulk(v: &'a [T]) -> Self {
-        let mut arguments = <DB as HasArguments<'a>>::Arguments::default();
-        v.iter().for_each(|row| row.arguments(&mut arguments));
-        Self::create_insert(arguments, v.len())
+        Self::new_insert_bulk(std::slice::from_ref(v))
     }
 
-    fn create_insert(arguments: <DB as HasArguments<'a>>::Arguments, rows_count: usize) -> Self {
+    pub(crate) fn new_insert_bulk(rows: &'a [T]) -> Self {
+        let mut sql_builder = SqlBuilder::from_insert(
+            TargetDatabase::new::<DB>(),
+            T::table(),
+            T::insert_columns().into_iter().map(|(_f, c)| c).collect(),
+            rows.len(),
+        );
+        // only rendered by the upserts
+        if let Some(c) = T::updated_at() {
+            sql_builder.add_update_section(UpdateSetSection::SetColumn(c));
+        }
         Self {
-            arguments,
-            sql_builder: SqlBuilder::from_insert(
-                TargetDatabase::new::<DB>(),
-                T::table(),
-                T::columns().into_iter().map(|(_f, c)| c).collect(),
-                rows_count,
-            ),
+            arguments: <DB as HasArguments<'a>>::Arguments::default(),
+            sql_builder,
+            timeout: None,
+            rows: rows.iter().map(|row| row.insert_values()).collect(),
+            tenants: rows.iter().map(|row| row.tenant_check()).collect(),
+            values: 0,
+            check_stale: false,
+            after_success: None,
+            end_values: vec![],
+            scoped: true,
             _a: Default::default(),
         }
     }
 
     pub(crate) fn new_select() -> Self {
-        Self {
+        let mut query = Self {
             arguments: <DB as HasArguments<'a>>::Arguments::default(),
             sql_builder: SqlBuilder::from_select(
                 TargetDatabase::new::<DB>(),
                 T::table(),
                 T::columns().into_iter().map(|(_f, c)| c).collect()
             ),
+            timeout: None,
+            rows: vec![],
+            tenants: vec![],
+            values: 0,
+            check_stale: false,
+            after_success: None,
+            end_values: vec![],
+            scoped: true,
             _a: Default::default(),
+        };
+        query.scope();
+        query
+    }
+
+    pub(crate) fn new_find(key: <T as PrimaryKey<'a, DB>>::Key) -> Self where T: PrimaryKey<'a, DB> {
+        let mut query = Self::new_select();
+        T::key_arguments(key, &mut query.arguments);
+        T::primary_key().iter().for_each(|c| query.sql_builder.add_where(Condition::AndEq(c)));
+        query.values += T::primary_key().len();
+        query
+    }
+
+    pub(crate) fn new_find_many<I>(keys: I) -> Self
+        where
+            T: PrimaryKey<'a, DB>,
+            I: IntoIterator<Item = <T as PrimaryKey<'a, DB>>::Key> {
+        let mut query = Self::new_select();
+        let rows = keys.into_iter().map(|key| T::key_arguments(key, &mut query.arguments)).count();
+        query.sql_builder.add_where(Condition::AndRowIn(T::primary_key(), rows));
+        query.values += rows * T::primary_key().len();
+        query
+    }
+
+    pub(crate) fn new_update_by_pk(v: &'a mut T) -> Self where T: PrimaryKey<'a, DB> {
+        let mut query = Self::new_update();
+        let columns = T::update_columns();
+        columns.iter().for_each(|(_f, c)| query.sql_builder.add_update_section(UpdateSetSection::SetValue(c)));
+        query.values += columns.len();
+        query.where_key_and_version(v, None);
+        query
+    }
+
+    // only the given columns, nothing to execute if there are none
+    pub(crate) fn new_update_columns(v: &'a mut T, columns: &[&'a str]) -> Self where T: PrimaryKey<'a, DB> {
+        let mut query = Self::new_update();
+        // without the primary key, the generated columns and the ones managed by cherry
+        let allowed = T::update_columns();
+        let columns = columns.iter().copied()
+            .filter(|c| allowed.iter().any(|(_f, a)| a == c))
+            .collect::<Vec<&str>>();
+        if columns.is_empty() {
+            return query;
+        }
+        columns.iter().for_each(|c| query.sql_builder.add_update_section(UpdateSetSection::SetValue(c)));
+        query.values += columns.len();
+        query.where_key_and_version(v, Some(&columns));
+        query
+    }
+
+    pub(crate) fn new_delete_by_pk(v: &'a T) -> Self where T: PrimaryKey<'a, DB> {
+        let mut query = Self::new_delete();
+        query.where_primary_key(v);
+        query.where_version(v);
+        query
+    }
+
+    pub(crate) fn new_save(v: &'a T) -> Self where T: PrimaryKey<'a, DB> {
+        let mut query = Self::new_insert(v);
+        let keys = T::primary_key();
+        let columns = T::insert_columns().into_iter()
+            .map(|(_f, c)| c)
+            // a stale copy doesn't restore a trashed row either
+            .filter(|c| !keys.contains(c) && Some(*c) != T::created_at() && Some(*c) != T::soft_delete())
+            .collect::<Vec<&str>>();
+        // nothing to update besides the key
+        match columns.is_empty() {
+            true => query.sql_builder.conflict_with(InsertOnConflict::Ignore),
+            _ => query.sql_builder.conflict_with(InsertOnConflict::Update),
+        }
+        keys.iter().for_each(|c| query.sql_builder.add_conflict_column(c));
+        columns.into_iter().for_each(|c| query.sql_builder.add_update_section(match Some(c) == T::version() {
+            // bumped, never overwritten by the saved value
+            true => UpdateSetSection::Increment(T::table(), c),
+            _ => UpdateSetSection::SetColumn(c),
+        }));
+        // Optimistic locking, the existing row is only updated if its version is the saved one.
+        // MySQL has no condition on `ON DUPLICATE KEY UPDATE`, refused when executed.
+        if let Some(c) = T::version() {
+            query.sql_builder.add_where(Condition::AndEqInserted(T::table(), c));
+            query.check_stale = true;
+        }
+        query
+    }
+
+    // AND key = ?, with the key values of `v`
+    fn where_primary_key(&mut self, v: &'a T) {
+        T::primary_key().iter().for_each(|c| self.sql_builder.add_where(Condition::AndEq(c)));
+        v.primary_key_arguments(&mut self.arguments);
+        self.values += T::primary_key().len();
+    }
+
+    // The instance update, AND key = ? AND version = ? and increment the version, in `v` too once
+    // updated. The values of the SET clause are bound by `v`, before the key.
+    fn where_key_and_version(&mut self, v: &'a mut T, columns: Option<&[&str]>) where T: PrimaryKey<'a, DB> {
+        T::primary_key().iter().for_each(|c| self.sql_builder.add_where(Condition::AndEq(c)));
+        self.values += T::primary_key().len();
+        if let Some(c) = T::version() {
+            self.sql_builder.add_update_section(UpdateSetSection::Increment(T::table(), c));
+            self.sql_builder.add_where(Condition::AndEq(c));
+            self.values += 1;
+            self.check_stale = true;
+        }
+        self.after_success = v.update_by_key_arguments(columns, &mut self.arguments);
+    }
+
+    // Optimistic locking of the deletes, AND version = ?
+    fn where_version(&mut self, v: &'a T) {
+        if let Some(c) = T::version() {
+            self.sql_builder.add_where(Condition::AndEq(c));
+            v.column_arguments(c, &mut self.arguments);
+            self.values += 1;
+            self.check_stale = true;
         }
     }
 
     pub(crate) fn new_update() -> Self {
-        Self {
+        let mut query = Self {
             arguments: <DB as HasArguments<'a>>::Arguments::default(),
             sql_builder: SqlBuilder::from_update(TargetDatabase::new::<DB>(), T::table()),
+            timeout: None,
+            rows: vec![],
+            tenants: vec![],
+            values: 0,
+            check_stale: false,
+            after_success: None,
+            end_values: vec![],
+            scoped: true,
             _a: Default::default(),
+        };
+        // bound first, before the values of `set()` and the conditions
+        if let Some(c) = T::updated_at() {
+            query.sql_builder.add_update_section(UpdateSetSection::Touch(c));
+            T::touch_arguments(&mut query.arguments);
+            query.values += 1;
         }
+        query.scope();
+        query
     }
 
     pub(crate) fn new_delete() -> Self {
-        Self {
+        match T::soft_delete() {
+            Some(c) => {
+                let mut query = Self::new_update();
+                query.sql_builder.add_update_section(UpdateSetSection::SetValue(c));
+                T::soft_delete_arguments(&mut query.arguments);
+                query.values += 1;
+                query
+            }
+            _ => Self::new_force_delete(),
+        }
+    }
+
+    pub(crate) fn new_restore() -> Self {
+        let mut query = Self::new_update();
+        if let Some(c) = T::soft_delete() {
+            query.sql_builder.add_update_section(UpdateSetSection::SetNull(c));
+        }
+        query.only_trashed()
+    }
+
+    pub(crate) fn new_force_delete() -> Self {
+        let mut query = Self {
             arguments: <DB as HasArguments<'a>>::Arguments::default(),
             sql_builder: SqlBuilder::from_delete(TargetDatabase::new::<DB>(), T::table()),
+            timeout: None,
+            rows: vec![],
+            tenants: vec![],
+            values: 0,
+            check_stale: false,
+            after_success: None,
+            end_values: vec![],
+            scoped: true,
+            _a: Default::default(),
+        };
+        query.scope();
+        query.with_trashed()
+    }
+
+    // WHERE key IN (SELECT right FROM through WHERE left = ?), the `T` of a many to many relation
+    pub(crate) fn new_related<P>(parent: &P) -> Self
+        where
+            T: SingleKey<'a, DB>,
+            P: ManyToMany<'a, T, DB> {
+        let (through, left, right) = P::through();
+        let mut query = Self::new_select();
+        query.sql_builder.add_where(Condition::AndInSelect(T::primary_key()[0], through, right, left));
+        P::key_arguments(parent.key(), &mut query.arguments);
+        query.values += 1;
+        query
+    }
+
+    // INSERT INTO through (left, right), ignoring the pairs already there
+    pub(crate) fn new_attach<R, I>(parent: &T, keys: I) -> Self
+        where
+            T: ManyToMany<'a, R, DB>,
+            R: SingleKey<'a, DB>,
+            I: IntoIterator<Item = <R as PrimaryKey<'a, DB>>::Key> {
+        let (through, left, right) = T::through();
+        let mut arguments = <DB as HasArguments<'a>>::Arguments::default();
+        let rows = keys.into_iter().map(|key| {
+            T::key_arguments(parent.key(), &mut arguments);
+            R::key_arguments(key, &mut arguments);
+        }).count();
+        let mut sql_builder = SqlBuilder::from_insert(TargetDatabase::new::<DB>(), through, vec![left, right], rows);
+        sql_builder.conflict_with(InsertOnConflict::Ignore);
+        let mut query = Self::new_through(arguments, sql_builder);
+        query.values = rows * 2;
+        query
+    }
+
+    // DELETE FROM through WHERE left = ? AND right IN (..)
+    pub(crate) fn new_detach<R, I>(parent: &T, keys: I) -> Self
+        where
+            T: ManyToMany<'a, R, DB>,
+            R: SingleKey<'a, DB>,
+            I: IntoIterator<Item = <R as PrimaryKey<'a, DB>>::Key> {
+        Self::through_delete::<R, I>(parent, keys, Condition::AndIn)
+    }
+
+    // DELETE FROM through WHERE left = ? AND right NOT IN (..), the first half of a sync
+    pub(crate) fn new_detach_others<R, I>(parent: &T, keys: I) -> Self
+        where
+            T: ManyToMany<'a, R, DB>,
+            R: SingleKey<'a, DB>,
+            I: IntoIterator<Item = <R as PrimaryKey<'a, DB>>::Key> {
+        Self::through_delete::<R, I>(parent, keys, Condition::AndNotIn)
+    }
+
+    fn through_delete<R, I>(parent: &T, keys: I, condition: fn(&'a str, usize) -> Condition<'a>) -> Self
+        where
+            T: ManyToMany<'a, R, DB>,
+            R: SingleKey<'a, DB>,
+            I: IntoIterator<Item = <R as PrimaryKey<'a, DB>>::Key> {
+        let (through, left, right) = T::through();
+        let mut arguments = <DB as HasArguments<'a>>::Arguments::default();
+        T::key_arguments(parent.key(), &mut arguments);
+        let rows = keys.into_iter().map(|key| R::key_arguments(key, &mut arguments)).count();
+        let mut sql_builder = SqlBuilder::from_delete(TargetDatabase::new::<DB>(), through);
+        sql_builder.add_where(Condition::AndEq(left));
+        sql_builder.add_where(condition(right, rows));
+        let mut query = Self::new_through(arguments, sql_builder);
+        query.values = rows + 1;
+        query
+    }
+
+    // The join table has no entity, nothing of `T` is scoped or touched.
+    fn new_through(arguments: <DB as HasArguments<'a>>::Arguments, sql_builder: SqlBuilder<'a>) -> Self {
+        Self {
+            arguments,
+            sql_builder,
+            timeout: None,
+            rows: vec![],
+            tenants: vec![],
+            values: 0,
+            check_stale: false,
+            after_success: None,
+            end_values: vec![],
+            scoped: false,
+            _a: Default::default(),
+        }
+    }
+
+    // Rows per insert statement to stay under the parameter limit of the database.
+    pub(crate) fn chunk_size(&self) -> Result<usize, Error> {
+        let columns = self.sql_builder.table_columns.len();
+        let limit = self.sql_builder.db.max_parameters();
+        let total = self.rows.len() * columns + self.values;
+        if total <= limit || columns == 0 {
+            return Ok(self.rows.len().max(1));
+        }
+        // The other values can't be bound again for every chunk.
+        match self.values {
+            0 => Ok(limit / columns),
+            _ => Err(QueryError::TooManyParameters(total).into()),
+        }
+    }
+
+    // Nothing to execute, an update without a column to set or an insert without a row.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.sql_builder.is_empty_update() || self.sql_builder.is_empty_insert()
+    }
+
+    // A single statement can't bind more parameters than the database allows, the insert rows
+    // have to be split with `execute_chunked()`.
+    pub(crate) fn check_parameters(&self) -> Result<(), Error> {
+        let total = self.rows.len() * self.sql_builder.table_columns.len() + self.values;
+        match total <= self.sql_builder.db.max_parameters() {
+            true => Ok(()),
+            _ => Err(QueryError::TooManyParameters(total).into()),
+        }
+    }
+
+    // The statements of `execute_chunked()`: the insert rows split into chunks under the parameter
+    // limit, a single statement otherwise.
+    pub(crate) fn take_statements(&mut self)
+        -> Result<Vec<(String, <DB as HasArguments<'a>>::Arguments)>, Error> {
+        let size = self.chunk_size()?;
+        if size >= self.rows.len() {
+            return Ok(vec![(self.sql_builder.as_sql(), self.take_arguments()?)]);
+        }
+        self.check_save()?;
+        self.tenant_arguments(&mut Default::default())?;
+        let mut statements = vec![];
+        let mut rows = std::mem::take(&mut self.rows);
+        while !rows.is_empty() {
+            let rest = rows.split_off(size.min(rows.len()));
+            self.sql_builder.rows = rows.len();
+            let mut arguments = <DB as HasArguments<'a>>::Arguments::default();
+            rows.into_iter().for_each(|add| add(&mut arguments));
+            statements.push((self.sql_builder.as_sql(), arguments));
+            rows = rest;
+        }
+        Ok(statements)
+    }
+
+    // The conditions AND-ed with the selects, updates and deletes: the `scope` attributes, the
+    // tenant and `deleted_at IS NULL` of the soft deleted entities.
+    fn scope(&mut self) {
+        T::scopes().iter().for_each(|sql| self.sql_builder.add_scope(Condition::AndRaw(sql)));
+        if let Some(c) = T::tenant() {
+            self.sql_builder.add_scope(Condition::AndEq(c));
+        }
+        if let Some(c) = T::soft_delete() {
+            self.sql_builder.add_scope(Condition::AndIsNull(c));
+        }
+    }
+
+    // Without the `scope` attributes and the tenant, the soft deleted rows are still skipped. The
+    // unscoped inserts write the tenant of the rows out of a `TenantId` scope.
+    pub fn unscoped(mut self) -> Self {
+        self.scoped = false;
+        self.sql_builder.where_clause.retain_scopes(|s| match s {
+            Condition::AndRaw(_) => false,
+            Condition::AndEq(c) => Some(*c) != T::tenant(),
+            _ => true,
+        });
+        self
+    }
+
+    // The values bound so far, then the insert rows or the tenant, which is rendered after the other
+    // conditions, then the LIMIT and OFFSET values and the values of an upsert.
+    pub(crate) fn take_arguments(&mut self) -> Result<<DB as HasArguments<'a>>::Arguments, Error> {
+        self.check_save()?;
+        let mut arguments = std::mem::take(&mut self.arguments);
+        self.tenant_arguments(&mut arguments)?;
+        self.rows.drain(..).for_each(|add| add(&mut arguments));
+        self.end_values.drain(..).for_each(|add| add(&mut arguments));
+        Ok(arguments)
+    }
+
+    // Once executed without error.
+    pub(crate) fn succeeded(&mut self) {
+        if let Some(f) = self.after_success.take() {
+            f();
+        }
+    }
+
+    // A stale row can't be told from an inserted one by the rows affected of `ON DUPLICATE KEY
+    // UPDATE`, MySQL doesn't save the versioned entities rather than overwrite a concurrent change.
+    fn check_save(&self) -> Result<(), Error> {
+        let versioned = self.check_stale && matches!(self.sql_builder.sql_type, SqlType::Insert);
+        match versioned && matches!(self.sql_builder.db, TargetDatabase::MySql) {
+            true => Err(QueryError::VersionedSave(T::table()).into()),
+            _ => Ok(()),
+        }
+    }
+
+    // Bind the tenant of the scope, the scoped queries fail without one rather than reading or
+    // writing the rows of every tenant. The insert rows bind their own, it has to be the scope's.
+    fn tenant_arguments(&mut self, arguments: &mut <DB as HasArguments<'a>>::Arguments) -> Result<(), Error> {
+        if !self.scoped || T::tenant().is_none() {
+            return Ok(());
+        }
+        let found = match self.sql_builder.sql_type {
+            SqlType::Insert => T::tenant_arguments(&mut Default::default()),
+            _ => T::tenant_arguments(arguments),
+        };
+        if !found {
+            return Err(QueryError::MissingTenant(T::table()).into());
+        }
+        match self.tenants.drain(..).all(|check| check()) {
+            true => Ok(()),
+            _ => Err(QueryError::TenantMismatch(T::table()).into()),
+        }
+    }
+
+    // Include the soft deleted rows.
+    pub fn with_trashed(mut self) -> Self {
+        if let Some(c) = T::soft_delete() {
+            self.sql_builder.where_clause.retain_scopes(|s| !matches!(s,
+                Condition::AndIsNull(v) | Condition::AndIsNotNull(v) if *v == c
+            ));
+        }
+        self
+    }
+
+    // Only the soft deleted rows.
+    pub fn only_trashed(self) -> Self {
+        let mut query = self.with_trashed();
+        if let Some(c) = T::soft_delete() {
+            query.sql_builder.add_scope(Condition::AndIsNotNull(c));
+        }
+        query
+    }
+
+    // Fail with `QueryError::Timeout` if the query does not complete in time.
+    #[cfg(any(feature = "async-std", feature = "tokio"))]
+    pub fn timeout(mut self, duration: Duration) -> Self {
+        self.timeout = Some(duration);
+        self
+    }
+
+}
+
+#[cfg(feature = "postgres")]
+impl<'a, T> Query<'a, T, sqlx::Postgres>
+    where
+        T: crate::unnest::Unnest<'a> {
+
+    pub(crate) fn new_insert_unnest(v: &'a [T]) -> Self {
+        let mut sql_builder = SqlBuilder::from_insert(
+            TargetDatabase::Postgres,
+            T::table(),
+            T::insert_columns().into_iter().map(|(_f, c)| c).collect(),
+            v.len(),
+        );
+        sql_builder.unnest(T::array_types());
+        if let Some(c) = T::updated_at() {
+            sql_builder.add_update_section(UpdateSetSection::SetColumn(c));
+        }
+        Self {
+            arguments: sqlx::postgres::PgArguments::default(),
+            sql_builder,
+            timeout: None,
+            // bound as arrays, one statement whatever the count of rows
+            rows: vec![T::array_values(v)],
+            tenants: v.iter().map(|row| row.tenant_check()).collect(),
+            values: 0,
+            check_stale: false,
+            after_success: None,
+            end_values: vec![],
+            scoped: true,
             _a: Default::default(),
         }
     }
@@ -80,8 +534,20 @@ impl<'a, T, DB> Provider<'a, DB> for Query<'a, T, DB>
     where T: Cherry<'a, DB> + 'a,
           DB: Database {
 
+    type Entity = T;
+
     fn add_value<V>(&mut self, v: V) where V: Encode<'a, DB> + Type<DB> + Send + 'a {
-        self.arguments.add(v);
+        // the values of an upsert come after the insert rows, bound when executed
+        match self.rows.is_empty() {
+            true => self.arguments.add(v),
+            _ => self.end_values.push(Box::new(move |arguments| arguments.add(v))),
+        }
+        self.values += 1;
+    }
+
+    fn add_end_value<V>(&mut self, v: V) where V: Encode<'a, DB> + Type<DB> + Send + 'a {
+        self.end_values.push(Box::new(move |arguments| arguments.add(v)));
+        self.values += 1;
     }
 
     fn sql_builder(&mut self) -> &mut SqlBuilder<'a> {
@@ -0,0 +1,58 @@
+use anyhow::Error;
+use futures_core::future::BoxFuture;
+use sqlx::{Acquire, Database, Executor};
+
+use crate::{Cherry, PrimaryKey, QueryExecutor, SingleKey};
+use crate::query::Query;
+
+// Implemented by the derive for `#[cherry(has_many(R, foreign_key = ".."))]`, for
+// `PrimaryKey::load_related` and `PrimaryKey::with_related`.
+pub trait HasMany<'a, R, DB>: PrimaryKey<'a, DB> where R: Cherry<'a, DB>, DB: Database {
+
+    // the column of `R` referencing the primary key of `Self`
+    fn foreign_key() -> &'static str;
+}
+
+// Implemented by the derive for `#[cherry(many_to_many(R, through = "..", left = "..", right = ".."))]`.
+// The join table has no entity, its rows are only the pairs of keys.
+pub trait ManyToMany<'a, R, DB>: SingleKey<'a, DB> where R: SingleKey<'a, DB>, DB: Database {
+
+    // the join table, its column referencing `Self` and its column referencing `R`
+    fn through() -> (&'static str, &'static str, &'static str);
+
+    fn related(&self) -> Query<'a, R, DB> {
+        Query::new_related(self)
+    }
+
+    // Insert the pairs, ignoring the ones already there.
+    fn attach<I>(&self, keys: I) -> Query<'a, Self, DB>
+        where I: IntoIterator<Item = <R as PrimaryKey<'a, DB>>::Key> {
+        Query::new_attach::<R, I>(self, keys)
+    }
+
+    fn detach<I>(&self, keys: I) -> Query<'a, Self, DB>
+        where I: IntoIterator<Item = <R as PrimaryKey<'a, DB>>::Key> {
+        Query::new_detach::<R, I>(self, keys)
+    }
+
+    // Keep exactly these `R`: delete the other pairs and attach the missing ones, in a transaction.
+    fn sync<'e, I, A>(&self, keys: I, a: A) -> BoxFuture<'e, Result<(), Error>>
+        where
+            'a: 'e,
+            I: IntoIterator<Item = <R as PrimaryKey<'a, DB>>::Key>,
+            <R as PrimaryKey<'a, DB>>::Key: Clone,
+            A: Acquire<'e, Database = DB> + Send + 'e,
+            for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+            Query<'a, Self, DB>: QueryExecutor<'a, Self, DB> {
+        let keys = keys.into_iter().collect::<Vec<_>>();
+        let detach = Query::new_detach_others::<R, _>(self, keys.clone());
+        let attach = Query::new_attach::<R, _>(self, keys);
+        Box::pin(async move {
+            let mut tx = a.begin().await?;
+            detach.execute(&mut *tx).await?;
+            attach.execute(&mut *tx).await?;
+            tx.commit().await?;
+            Ok(())
+        })
+    }
+}
@@ -13,6 +13,7 @@ pub struct SqlBuilder<'a> {
 
     pub(crate) rows: usize, // insert row count
     pub(crate) conflict: (InsertOnConflict, Vec<&'a str>), // conflict action and conflict columns
+    pub(crate) unnest_types: Vec<String>, // postgres array type of every column, insert from UNNEST
 
     pub(crate) update_set_clause: UpdateSetClause<'a>,
     pub(crate) select_column_clause: SelectColumnClause<'a>,
@@ -30,6 +31,7 @@ impl<'a> SqlBuilder<'a> {
             table_columns: columns,
             rows,
             conflict: (None, vec![]),
+            unnest_types: vec![],
             update_set_clause: Default::default(),
             select_column_clause: Default::default(),
             where_clause: Default::default(),
@@ -45,6 +47,7 @@ impl<'a> SqlBuilder<'a> {
             table_columns: columns,
             rows: 0,
             conflict: (None, vec![]),
+            unnest_types: vec![],
             update_set_clause: Default::default(),
             select_column_clause: Default::default(),
             where_clause: Default::default(),
@@ -60,6 +63,7 @@ impl<'a> SqlBuilder<'a> {
             table_columns: vec![],
             rows: 0,
             conflict: (None, vec![]),
+            unnest_types: vec![],
             update_set_clause: Default::default(),
             select_column_clause: Default::default(),
             where_clause: Default::default(),
@@ -75,6 +79,7 @@ impl<'a> SqlBuilder<'a> {
             table_columns: vec![],
             rows: 0,
             conflict: (None, vec![]),
+            unnest_types: vec![],
             update_set_clause: Default::default(),
             select_column_clause: Default::default(),
             where_clause: Default::default(),
@@ -82,6 +87,11 @@ impl<'a> SqlBuilder<'a> {
         }
     }
 
+    #[cfg(feature = "postgres")]
+    pub(crate) fn unnest(&mut self, types: Vec<String>) {
+        self.unnest_types = types;
+    }
+
     pub(crate) fn conflict_with(&mut self, conflict: InsertOnConflict) {
         self.conflict.0 = conflict;
     }
@@ -103,6 +113,10 @@ impl<'a> SqlBuilder<'a> {
         self.where_clause.add(condition);
     }
 
+    pub(crate) fn add_scope(&mut self, condition: Condition<'a>) {
+        self.where_clause.add_scope(condition);
+    }
+
     pub(crate) fn add_end_section(&mut self, section: EndSection<'a>) {
         self.end_clause.add(section);
     }
@@ -120,13 +134,28 @@ impl<'a> SqlBuilder<'a> {
         self.db.quote(self.table)
     }
 
+    // an update without any column to set, there is nothing to execute
+    pub(crate) fn is_empty_update(&self) -> bool {
+        matches!(self.sql_type, SqlType::Update) && self.update_set_clause.is_empty()
+    }
+
+    // an insert without any row, `VALUES` can't be empty, there is nothing to execute
+    pub(crate) fn is_empty_insert(&self) -> bool {
+        matches!(self.sql_type, SqlType::Insert) && self.rows == 0
+    }
+
     pub(crate) fn as_sql(&self) -> String {
-        match self.sql_type {
+        let sql = match self.sql_type {
             SqlType::Insert => self.as_insert_sql(),
             SqlType::Update => self.as_update_sql(),
             SqlType::Delete => self.as_delete_sql(),
             SqlType::Select => self.as_select_sql(),
-        }
+        };
+        self.db.placeholders(sql)
+    }
+
+    pub(crate) fn as_explain_sql(&self, analyze: bool) -> String {
+        format!("{} {}", self.db.explain(analyze), self.as_sql())
     }
 
     fn as_select_sql(&self) -> String {
@@ -162,7 +191,7 @@ impl<'a> SqlBuilder<'a> {
 
     // *********************************** Insert *************************************
 
-    pub(crate) fn as_insert_sql(&self) -> String {
+    fn as_insert_sql(&self) -> String {
         match self.db {
             TargetDatabase::MySql => self.mysql(),
             TargetDatabase::Sqlite => self.sqlite(),
@@ -186,7 +215,7 @@ impl<'a> SqlBuilder<'a> {
             ),
             // https://dev.mysql.com/doc/refman/8.0/en/insert-on-duplicate.html
             Update => format!(
-                "INSERT INTO {} ({}) VALUES ({}) AS new ON DUPLICATE KEY UPDATE {}",
+                "INSERT INTO {} ({}) VALUES {} AS new ON DUPLICATE KEY UPDATE {}",
                 self.table(), self.table_columns(), self.values_holder(),
                 self.update_set_clause.as_clause(self.db).unwrap_or_default(),
             ),
@@ -220,17 +249,17 @@ impl<'a> SqlBuilder<'a> {
     fn postgres(&self) -> String {
         match self.conflict.0 {
             None => format!(
-                "INSERT INTO {} ({}) VALUES {}",
-                self.table(), self.table_columns(), self.values_holder(),
+                "INSERT INTO {} ({}) {}",
+                self.table(), self.table_columns(), self.postgres_values(),
             ),
             Ignore => format!(
-                "INSERT INTO {} ({}) VALUES {} ON CONFLICT{} DO NOTHING",
-                self.table(), self.table_columns(), self.values_holder(),
+                "INSERT INTO {} ({}) {} ON CONFLICT{} DO NOTHING",
+                self.table(), self.table_columns(), self.postgres_values(),
                 self.conflict_columns(),
             ),
             Update => format!(
-                "INSERT INTO {} ({}) VALUES {} ON CONFLICT{} DO UPDATE SET {}{}",
-                self.table(), self.table_columns(), self.values_holder(),
+                "INSERT INTO {} ({}) {} ON CONFLICT{} DO UPDATE SET {}{}",
+                self.table(), self.table_columns(), self.postgres_values(),
                 self.conflict_columns(),
                 self.update_set_clause.as_clause(self.db).unwrap_or_default(),
                 self.where_clause.as_sql(self.db).map(|v| format!(" WHERE {}", v)).unwrap_or_default()
@@ -257,7 +286,23 @@ impl<'a> SqlBuilder<'a> {
             .join(", ")
     }
 
+    fn postgres_values(&self) -> String {
+        match self.unnest_types.is_empty() {
+            true => format!("VALUES {}", self.values_holder()),
+            _ => {
+                let arrays = self.unnest_types.iter()
+                    .map(|t| format!("?::{}", t))
+                    .collect::<Vec<String>>().join(", ");
+                format!("SELECT * FROM UNNEST({})", arrays)
+            }
+        }
+    }
+
+    // none for `ON CONFLICT DO NOTHING` on any constraint
     fn conflict_columns(&self) -> String {
+        if self.conflict.1.is_empty() {
+            return String::new();
+        }
         let columns = self.conflict.1.iter()
             .map(|c| self.db.quote(c))
             .collect::<Vec<String>>()
@@ -267,4 +312,90 @@ impl<'a> SqlBuilder<'a> {
 
 
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "postgres")]
+    fn test_insert_unnest() {
+        let mut builder = SqlBuilder::from_insert(TargetDatabase::Postgres, "user", vec!["id", "name"], 3);
+        builder.unnest(vec!["INT4[]".into(), "TEXT[]".into()]);
+        builder.conflict_with(Ignore);
+        builder.add_conflict_column("id");
+        assert_eq!(
+            r#"INSERT INTO "user" ("id", "name") SELECT * FROM UNNEST($1::INT4[], $2::TEXT[]) ON CONFLICT("id") DO NOTHING"#,
+            builder.as_sql()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "postgres")]
+    fn test_insert_unnest_update_on_conflict() {
+        let mut builder = SqlBuilder::from_insert(TargetDatabase::Postgres, "user", vec!["id", "name"], 3);
+        builder.unnest(vec!["INT4[]".into(), "TEXT[]".into()]);
+        builder.conflict_with(Update);
+        builder.add_conflict_column("id");
+        builder.add_update_section(UpdateSetSection::SetValue("name"));
+        builder.add_where(Condition::AndEq("id"));
+        assert_eq!(
+            r#"INSERT INTO "user" ("id", "name") SELECT * FROM UNNEST($1::INT4[], $2::TEXT[]) ON CONFLICT("id") DO UPDATE SET "name" = $3 WHERE "id" = $4"#,
+            builder.as_sql()
+        );
+    }
+
+    #[test]
+    fn test_insert_ignore_any_conflict() {
+        let mut builder = SqlBuilder::from_insert(TargetDatabase::Postgres, "post_tags", vec!["post_id", "tag_id"], 1);
+        builder.conflict_with(Ignore);
+        assert_eq!(
+            r#"INSERT INTO "post_tags" ("post_id", "tag_id") VALUES ($1, $2) ON CONFLICT DO NOTHING"#,
+            builder.as_sql()
+        );
+    }
+
+    #[test]
+    fn test_insert_update_on_conflict() {
+        let mut builder = SqlBuilder::from_insert(TargetDatabase::MySql, "user", vec!["id", "name"], 1);
+        builder.conflict_with(Update);
+        builder.add_update_section(UpdateSetSection::SetColumn("name"));
+        assert_eq!(
+            "INSERT INTO `user` (`id`, `name`) VALUES (?, ?) AS new ON DUPLICATE KEY UPDATE `name` = new.`name`",
+            builder.as_sql()
+        );
+
+        let mut builder = SqlBuilder::from_insert(TargetDatabase::Postgres, "user", vec!["id", "name"], 1);
+        builder.conflict_with(Update);
+        builder.add_conflict_column("id");
+        builder.add_update_section(UpdateSetSection::SetColumn("name"));
+        assert_eq!(
+            r#"INSERT INTO "user" ("id", "name") VALUES ($1, $2) ON CONFLICT("id") DO UPDATE SET "name" = excluded."name""#,
+            builder.as_sql()
+        );
+    }
+
+    #[test]
+    fn test_insert_update_version_on_conflict() {
+        let mut builder = SqlBuilder::from_insert(TargetDatabase::Postgres, "doc", vec!["id", "version"], 1);
+        builder.conflict_with(Update);
+        builder.add_conflict_column("id");
+        builder.add_update_section(UpdateSetSection::Increment("doc", "version"));
+        builder.add_where(Condition::AndEqInserted("doc", "version"));
+        assert_eq!(
+            r#"INSERT INTO "doc" ("id", "version") VALUES ($1, $2) ON CONFLICT("id") DO UPDATE SET "version" = "doc"."version" + 1 WHERE "doc"."version" = excluded."version""#,
+            builder.as_sql()
+        );
+    }
+
+    #[test]
+    fn test_empty_insert() {
+        let builder = SqlBuilder::from_insert(TargetDatabase::Sqlite, "user", vec!["id", "name"], 0);
+        assert!(builder.is_empty_insert());
+        let builder = SqlBuilder::from_insert(TargetDatabase::Sqlite, "user", vec!["id", "name"], 1);
+        assert!(!builder.is_empty_insert());
+        assert!(!SqlBuilder::from_delete(TargetDatabase::Sqlite, "user").is_empty_insert());
+    }
+
+}
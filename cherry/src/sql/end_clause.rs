@@ -54,19 +54,19 @@ impl<'a> EndSection<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::sql::end_section::EndSection::{Limit, Offset, OrderBy};
+    use crate::sql::end_clause::EndSection::{Limit, Offset, OrderBy};
 
     use super::*;
 
     #[test]
     fn test_ending_simple() {
         let c  = vec![OrderBy("name", false)];
-        assert_eq!("ORDER BY name DESC", EndClause::gen_sections(TargetDatabase::Sqlite, &c));
+        assert_eq!(r#"ORDER BY "name" DESC"#, EndClause::gen_sections(TargetDatabase::Sqlite, &c));
     }
 
     #[test]
     fn test_ending_more() {
         let c  = vec![OrderBy("name", false), Limit(), Offset()];
-        assert_eq!("ORDER BY name DESC LIMIT ? OFFSET ?", EndClause::gen_sections(TargetDatabase::Sqlite, &c));
+        assert_eq!(r#"ORDER BY "name" DESC LIMIT ? OFFSET ?"#, EndClause::gen_sections(TargetDatabase::Sqlite, &c));
     }
 }
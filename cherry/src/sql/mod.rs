@@ -1,4 +1,5 @@
 use std::any::{type_name, TypeId};
+use std::time::Duration;
 
 use sqlx::Database;
 
@@ -42,6 +43,64 @@ impl TargetDatabase {
             TargetDatabase::Postgres | TargetDatabase::Sqlite => format!(r#""{}""#, s),
         }
     }
+
+    // Bind parameters allowed in one statement.
+    pub(crate) fn max_parameters(&self) -> usize {
+        match self {
+            // SQLITE_MAX_VARIABLE_NUMBER, since 3.32.0
+            TargetDatabase::Sqlite => 32766,
+            TargetDatabase::MySql | TargetDatabase::Postgres => 65535,
+        }
+    }
+
+    pub(crate) fn explain(&self, analyze: bool) -> &'static str {
+        match (self, analyze) {
+            // SQLite has no analyze form, the query plan is the best it can tell.
+            (TargetDatabase::Sqlite, _) => "EXPLAIN QUERY PLAN",
+            (TargetDatabase::Postgres, false) => "EXPLAIN (FORMAT JSON)",
+            (TargetDatabase::Postgres, true) => "EXPLAIN (ANALYZE, FORMAT JSON)",
+            (TargetDatabase::MySql, false) => "EXPLAIN FORMAT=JSON",
+            // https://dev.mysql.com/doc/refman/8.0/en/explain.html#explain-analyze
+            (TargetDatabase::MySql, true) => "EXPLAIN ANALYZE",
+        }
+    }
+
+    // Postgres numbers its placeholders, the `?` of the builder become `$1`, `$2`, .. in the order
+    // the values are bound. The `?` of the quoted strings and identifiers are left alone.
+    pub(crate) fn placeholders(&self, sql: String) -> String {
+        if !matches!(self, TargetDatabase::Postgres) {
+            return sql;
+        }
+        let mut numbered = String::with_capacity(sql.len());
+        let (mut n, mut quote) = (0, None);
+        for c in sql.chars() {
+            match (c, quote) {
+                ('?', None) => {
+                    n += 1;
+                    numbered.push_str(&format!("${}", n));
+                    continue;
+                }
+                ('\'' | '"', None) => quote = Some(c),
+                (c, Some(q)) if c == q => quote = None,
+                _ => {}
+            }
+            numbered.push(c);
+        }
+        numbered
+    }
+
+    // The statement letting the server cancel the statements of the current transaction running
+    // longer than `timeout`. Rounded up to milliseconds, a zero timeout would disable it. Only
+    // Postgres has one scoped to the transaction.
+    pub(crate) fn local_statement_timeout(&self, timeout: Duration) -> Option<String> {
+        match self {
+            TargetDatabase::Postgres => {
+                let millis = timeout.as_nanos().div_ceil(1_000_000).max(1);
+                Some(format!("SET LOCAL statement_timeout = {}", millis))
+            }
+            TargetDatabase::MySql | TargetDatabase::Sqlite => None,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -59,3 +118,33 @@ pub enum InsertOnConflict {
     Update,
     Replace
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_statement_timeout() {
+        let sql = TargetDatabase::Postgres.local_statement_timeout(Duration::from_millis(1500));
+        assert_eq!(Some("SET LOCAL statement_timeout = 1500"), sql.as_deref());
+        // rounded up, zero would disable it
+        let sql = TargetDatabase::Postgres.local_statement_timeout(Duration::from_micros(1500));
+        assert_eq!(Some("SET LOCAL statement_timeout = 2"), sql.as_deref());
+        let sql = TargetDatabase::Postgres.local_statement_timeout(Duration::ZERO);
+        assert_eq!(Some("SET LOCAL statement_timeout = 1"), sql.as_deref());
+
+        assert_eq!(None, TargetDatabase::Sqlite.local_statement_timeout(Duration::from_secs(1)));
+        assert_eq!(None, TargetDatabase::MySql.local_statement_timeout(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let sql = r#"SELECT "a?" FROM "t" WHERE "x" = ? AND "y" IN (?, ?) AND "z" <> 'it''s ?' LIMIT ?"#;
+        assert_eq!(
+            r#"SELECT "a?" FROM "t" WHERE "x" = $1 AND "y" IN ($2, $3) AND "z" <> 'it''s ?' LIMIT $4"#,
+            TargetDatabase::Postgres.placeholders(sql.into())
+        );
+        assert_eq!(sql, TargetDatabase::Sqlite.placeholders(sql.into()));
+        assert_eq!(sql, TargetDatabase::MySql.placeholders(sql.into()));
+    }
+}
@@ -9,13 +9,26 @@ pub(crate) struct UpdateSetClause<'a> {
 impl<'a> UpdateSetClause<'a> {
 
     pub(crate) fn add(&mut self, s: UpdateSetSection<'a>) {
+        // `updated_at` may be set by cherry already, a column can't be set twice
+        if let SetColumn(c) = s {
+            if self.sections.iter().any(|s| matches!(s, SetColumn(v) if *v == c)) {
+                return;
+            }
+        }
         self.sections.push(s);
     }
 
+    // nothing is set besides the `updated_at` column
+    pub(crate) fn is_empty(&self) -> bool {
+        self.sections.iter().all(|s| matches!(s, Touch(_)))
+    }
+
     pub(crate) fn as_clause(&self, db: TargetDatabase) -> Option<String> {
         let clause = self.sections.iter().map(|s| {
             match s {
-                SetValue(c) => format!("{} = ?", db.quote(c)),
+                SetValue(c) | Touch(c) => format!("{} = ?", db.quote(c)),
+                Increment(table, c) => format!("{0} = {1}.{0} + 1", db.quote(c), db.quote(table)),
+                SetNull(c) => format!("{} = NULL", db.quote(c)),
                 SetColumn(c) => match db {
                     // https://dev.mysql.com/doc/refman/8.0/en/insert-on-duplicate.html
                     MySql => format!(r#"{0} = new.{0}"#, db.quote(c)),
@@ -35,4 +48,7 @@ impl<'a> UpdateSetClause<'a> {
 pub enum UpdateSetSection<'a> {
     SetValue(&'a str),
     SetColumn(&'a str),
+    Increment(&'a str, &'a str), // the table and the column, ambiguous with `excluded` in an upsert
+    SetNull(&'a str),
+    Touch(&'a str), // set to the current time, `Cherry::touch_arguments()`
 }
\ No newline at end of file
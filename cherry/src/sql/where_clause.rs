@@ -5,6 +5,7 @@ use crate::sql::where_condition::Condition;
 pub struct WhereClause<'a> {
     conditions: Vec<Condition<'a>>,
     temp_conditions: Option<Vec<Condition<'a>>>,
+    scopes: Vec<Condition<'a>>, // added by cherry, AND-ed with the other conditions
 }
 
 impl<'a> WhereClause<'a> {
@@ -16,6 +17,14 @@ impl<'a> WhereClause<'a> {
         }
     }
 
+    pub fn add_scope(&mut self, condition: Condition<'a>) {
+        self.scopes.push(condition);
+    }
+
+    pub fn retain_scopes<F>(&mut self, f: F) where F: FnMut(&Condition<'a>) -> bool {
+        self.scopes.retain(f);
+    }
+
     pub fn make_temp(&mut self) {
         self.temp_conditions = Some(vec![]);
     }
@@ -25,9 +34,15 @@ impl<'a> WhereClause<'a> {
     }
 
     pub fn as_sql(&self, db: TargetDatabase) -> Option<String> {
-        match self.conditions.is_empty() {
-            true => None,
-            _ => Some(Self::gen_conditions(db, &self.conditions)),
+        match (self.conditions.is_empty(), self.scopes.is_empty()) {
+            (true, true) => None,
+            (_, true) => Some(Self::gen_conditions(db, &self.conditions)),
+            (true, _) => Some(Self::gen_conditions(db, &self.scopes)),
+            _ => Some(format!(
+                "({}) AND {}",
+                Self::gen_conditions(db, &self.conditions),
+                Self::gen_conditions(db, &self.scopes),
+            )),
         }
     }
 
@@ -55,7 +70,7 @@ impl<'a> WhereClause<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::sql::where_condition::Condition::{And, AndEq, OrGe, OrNotBetween};
+    use crate::sql::where_condition::Condition::{And, AndEq, AndIn, AndInSelect, AndIsNull, AndNotIn, AndRowIn, OrGe, OrIn, OrNotBetween, OrNotIn};
 
     use super::*;
 
@@ -72,4 +87,47 @@ mod tests {
         assert_eq!(left, WhereClause::gen_conditions(TargetDatabase::Sqlite, &c));
     }
 
+    #[test]
+    fn test_scopes() {
+        let mut clause = WhereClause::default();
+        clause.add_scope(AndIsNull("deleted_at"));
+        assert_eq!(r#""deleted_at" IS NULL"#, clause.as_sql(TargetDatabase::Sqlite).unwrap());
+        clause.add(AndEq("id"));
+        clause.add(OrGe("iq"));
+        let left = r#"("id" = ? OR "iq" >= ?) AND "deleted_at" IS NULL"#;
+        assert_eq!(left, clause.as_sql(TargetDatabase::Sqlite).unwrap());
+        clause.retain_scopes(|_| false);
+        assert_eq!(r#""id" = ? OR "iq" >= ?"#, clause.as_sql(TargetDatabase::Sqlite).unwrap());
+    }
+
+    #[test]
+    fn test_condition_row_in() {
+        let c = vec![AndRowIn(&["a", "b"], 2)];
+        let left = r#"("a", "b") IN ((?, ?), (?, ?))"#;
+        assert_eq!(left, WhereClause::gen_conditions(TargetDatabase::Sqlite, &c));
+        let c = vec![AndRowIn(&["id"], 3)];
+        assert_eq!(r#""id" IN (?, ?, ?)"#, WhereClause::gen_conditions(TargetDatabase::Sqlite, &c));
+        let c = vec![AndRowIn(&["id"], 0)];
+        assert_eq!("1 = 0", WhereClause::gen_conditions(TargetDatabase::Sqlite, &c));
+    }
+
+    #[test]
+    fn test_condition_in_select() {
+        let c = vec![AndInSelect("id", "post_tags", "tag_id", "post_id")];
+        let left = r#""id" IN (SELECT "tag_id" FROM "post_tags" WHERE "post_id" = ?)"#;
+        assert_eq!(left, WhereClause::gen_conditions(TargetDatabase::Sqlite, &c));
+    }
+
+    #[test]
+    fn test_condition_empty_in() {
+        let c = vec![AndEq("id"), AndIn("tag_id", 0), OrIn("tag_id", 0)];
+        let left = r#""id" = ? AND 1 = 0 OR 1 = 0"#;
+        assert_eq!(left, WhereClause::gen_conditions(TargetDatabase::Sqlite, &c));
+        let c = vec![AndEq("id"), AndNotIn("tag_id", 0), OrNotIn("tag_id", 0)];
+        let left = r#""id" = ? AND 1 = 1 OR 1 = 1"#;
+        assert_eq!(left, WhereClause::gen_conditions(TargetDatabase::Sqlite, &c));
+        let c = vec![AndIn("tag_id", 2)];
+        assert_eq!(r#""tag_id" IN (?, ?)"#, WhereClause::gen_conditions(TargetDatabase::Sqlite, &c));
+    }
+
 }
\ No newline at end of file
@@ -32,6 +32,16 @@ pub enum Condition<'a> {
     OrIn(&'a str, usize),
     AndNotIn(&'a str, usize),
     OrNotIn(&'a str, usize),
+    // `("a", "b") IN ((?, ?), ..)`, rows of the columns values
+    AndRowIn(&'a [&'a str], usize),
+    // `"id" IN (SELECT "tag_id" FROM "post_tags" WHERE "post_id" = ?)`, the column, the join
+    // table, its column selected and its column compared
+    AndInSelect(&'a str, &'a str, &'a str, &'a str),
+    // a scope of `#[cherry(scope = "..")]`, plain SQL
+    AndRaw(&'a str),
+    // `"t"."version" = excluded."version"`, the stored column of an upsert equal to the inserted
+    // one, the table and the column
+    AndEqInserted(&'a str, &'a str),
 
     AndEqColumn(&'a str),
     OrEqColumn(&'a str),
@@ -75,10 +85,27 @@ impl<'a> Condition<'a> {
             OrBetween(c) => format!("{} BETWEEN ? AND ?", db.quote(c)),
             AndNotBetween(c) => format!("{} NOT BETWEEN ? AND ?", db.quote(c)),
             OrNotBetween(c) => format!("{} NOT BETWEEN ? AND ?", db.quote(c)),
+            // nothing is in an empty list, `IN ()` is only valid for sqlite
+            AndIn(_, 0) | OrIn(_, 0) => "1 = 0".to_string(),
+            AndNotIn(_, 0) | OrNotIn(_, 0) => "1 = 1".to_string(),
             AndIn(c, n) => format!("{} IN ({})", db.quote(c), vec!["?"; *n].join(", ")),
             AndNotIn(c, n) => format!("{} NOT IN ({})", db.quote(c), vec!["?"; *n].join(", ")),
             OrIn(c, n) => format!("{} IN ({})", db.quote(c), vec!["?"; *n].join(", ")),
             OrNotIn(c, n) => format!("{} NOT IN ({})", db.quote(c), vec!["?"; *n].join(", ")),
+            AndRowIn(_, 0) => "1 = 0".to_string(),
+            AndRowIn([c], n) => format!("{} IN ({})", db.quote(c), vec!["?"; *n].join(", ")),
+            AndRowIn(columns, n) => {
+                let columns = columns.iter().map(|c| db.quote(c)).collect::<Vec<String>>();
+                let row = format!("({})", vec!["?"; columns.len()].join(", "));
+                format!("({}) IN ({})", columns.join(", "), vec![row; *n].join(", "))
+            }
+
+            AndInSelect(column, table, select, c) => format!(
+                "{} IN (SELECT {} FROM {} WHERE {} = ?)", db.quote(column), db.quote(select), db.quote(table), db.quote(c)
+            ),
+
+            AndRaw(sql) => format!("({})", sql),
+            AndEqInserted(table, c) => format!("{}.{} = {}", db.quote(table), db.quote(c), self.target_column(db, c)),
 
             AndEqColumn(c) => format!("{} = {}", self.target_column(db, c), c),
             OrEqColumn(c) => format!("{} = {}", self.target_column(db, c), c),
@@ -109,7 +136,7 @@ impl<'a> Condition<'a> {
         match &self {
             And(_) | AndEq(_) |
             AndGe(_) | AndGt(_) | AndLe(_) | AndLt(_) |
-            AndIn(_, _) | AndNotIn(_, _) |
+            AndIn(_, _) | AndNotIn(_, _) | AndRowIn(_, _) | AndInSelect(_, _, _, _) | AndRaw(_) | AndEqInserted(_, _) |
             AndIsNull(_) | AndIsNotNull(_) |
             AndBetween(_) | AndNotBetween(_) |
             AndEqColumn(_) |
@@ -0,0 +1,59 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+thread_local! {
+    // the tenant of the future being polled, set by `Scoped`
+    static TENANT: RefCell<Option<Arc<dyn Any + Send + Sync>>> = RefCell::new(None);
+}
+
+// The tenant of the queries run in `scope()`. The entities with a `#[cherry(tenant)]` field of
+// type `V` only select, update and delete its rows. The inserts are validated, the tenant is not
+// injected: `QueryError::TenantMismatch` for a row of another tenant:
+//
+// TenantId(42_u32).scope(async {
+//     Post::select().all(&pool).await // WHERE "tenant_id" = 42
+// }).await
+pub struct TenantId<V>(pub V);
+
+impl<V> TenantId<V> where V: Clone + Send + Sync + 'static {
+
+    // The tenant is a thread local set while `future` is polled, it is lost by the tasks and
+    // threads started from it, `spawn` or `spawn_blocking`: scope their futures too.
+    pub fn scope<F>(self, future: F) -> Scoped<F> where F: Future {
+        Scoped { tenant: Arc::new(self.0), future: Box::pin(future) }
+    }
+
+    // the tenant of the running scope, if of type `V`
+    pub fn current() -> Option<V> {
+        TENANT.with(|tenant| tenant.borrow().as_ref().and_then(|v| v.downcast_ref::<V>().cloned()))
+    }
+}
+
+// A future running with a tenant, it is set while the future is polled.
+pub struct Scoped<F> {
+    tenant: Arc<dyn Any + Send + Sync>,
+    future: Pin<Box<F>>,
+}
+
+impl<F> Future for Scoped<F> where F: Future {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let tenant = Some(self.tenant.clone());
+        let _outer = Restore(TENANT.with(|v| v.replace(tenant)));
+        self.future.as_mut().poll(cx)
+    }
+}
+
+// Restore the outer tenant, even if the future panics.
+struct Restore(Option<Arc<dyn Any + Send + Sync>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        TENANT.with(|v| *v.borrow_mut() = self.0.take());
+    }
+}
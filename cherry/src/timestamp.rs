@@ -0,0 +1,58 @@
+// The types of `#[cherry(created_at)]` and `#[cherry(updated_at)]` fields, bound with the
+// current time by inserts and updates.
+pub trait Timestamp {
+    fn now() -> Self;
+}
+
+impl<T> Timestamp for Option<T> where T: Timestamp {
+    fn now() -> Self {
+        Some(T::now())
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono {
+    use sqlx::types::chrono::{DateTime, Local, NaiveDateTime, Utc};
+
+    use super::Timestamp;
+
+    impl Timestamp for DateTime<Utc> {
+        fn now() -> Self {
+            Utc::now()
+        }
+    }
+
+    impl Timestamp for DateTime<Local> {
+        fn now() -> Self {
+            Local::now()
+        }
+    }
+
+    // UTC
+    impl Timestamp for NaiveDateTime {
+        fn now() -> Self {
+            Utc::now().naive_utc()
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time {
+    use sqlx::types::time::{OffsetDateTime, PrimitiveDateTime};
+
+    use super::Timestamp;
+
+    impl Timestamp for OffsetDateTime {
+        fn now() -> Self {
+            OffsetDateTime::now_utc()
+        }
+    }
+
+    // UTC
+    impl Timestamp for PrimitiveDateTime {
+        fn now() -> Self {
+            let now = OffsetDateTime::now_utc();
+            PrimitiveDateTime::new(now.date(), now.time())
+        }
+    }
+}
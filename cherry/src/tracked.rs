@@ -0,0 +1,76 @@
+use std::ops::Deref;
+
+use sqlx::Database;
+
+use crate::{Column, PrimaryKey};
+use crate::query::Query;
+
+// An entity recording the columns changed by `set()`, to update only them. The primary key
+// columns can't be set, the row is updated by its key, nor the columns `update()` never sets:
+//
+// let mut user = Tracked::new(User::find_or_fail(1, &pool).await?);
+// user.set(User::NAME, "Joe".to_string());
+// user.update().execute(&pool).await?; // UPDATE user SET name = ? WHERE id = ?
+pub struct Tracked<T> {
+    value: T,
+    changed: Vec<&'static str>,
+}
+
+impl<T> Tracked<T> {
+
+    pub fn new(value: T) -> Self {
+        Self { value, changed: vec![] }
+    }
+
+    pub fn set<F>(&mut self, column: Column<T, F>, value: F) {
+        *column.field_mut(&mut self.value) = value;
+        if !self.changed.contains(&column.name()) {
+            self.changed.push(column.name());
+        }
+    }
+
+    // columns changed since loaded or last updated
+    pub fn changed(&self) -> &[&'static str] {
+        &self.changed
+    }
+
+    pub fn is_changed(&self) -> bool {
+        !self.changed.is_empty()
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    // Set the changed columns by primary key, the changes are cleared once it's executed without
+    // error. The query does nothing when there are none. The version is incremented along.
+    pub fn update<'a, DB>(&'a mut self) -> Query<'a, T, DB>
+        where
+            T: PrimaryKey<'a, DB>,
+            DB: Database {
+        let mut query = Query::new_update_columns(&mut self.value, &self.changed.clone());
+        let changed = &mut self.changed;
+        let increment = query.after_success.take();
+        query.after_success = Some(Box::new(move || {
+            changed.clear();
+            if let Some(increment) = increment {
+                increment();
+            }
+        }));
+        query
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> From<T> for Tracked<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
@@ -0,0 +1,50 @@
+use sqlx::{Encode, Postgres, Type, TypeInfo};
+use sqlx::encode::IsNull;
+use sqlx::postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo};
+
+use crate::{Cherry, InsertValues};
+use crate::query::Query;
+
+// Insert rows with one array bound per column:
+// INSERT INTO t (a, b) SELECT * FROM UNNEST($1::INT4[], $2::TEXT[])
+// The statement does not grow with the rows count, so it can be prepared once and has no
+// parameter limit.
+pub trait Unnest<'a>: Cherry<'a, Postgres> {
+
+    // array type of every column, in the order of `insert_columns()`
+    fn array_types() -> Vec<String>;
+
+    // one array per column, bound when the insert is executed
+    fn array_values(v: &'a [Self]) -> InsertValues<'a, Postgres>;
+
+    fn insert_unnest(v: &'a [Self]) -> Query<'a, Self, Postgres> {
+        Query::new_insert_unnest(v)
+    }
+}
+
+// The values of one column. `Vec<&V>` can't be bound directly, references have no array type.
+#[doc(hidden)]
+pub struct UnnestArray<'a, V>(pub Vec<&'a V>);
+
+impl<'a, V> UnnestArray<'a, V> where V: PgHasArrayType {
+    pub fn type_name() -> String {
+        V::array_type_info().name().to_string()
+    }
+}
+
+impl<'a, V> Type<Postgres> for UnnestArray<'a, V> where V: PgHasArrayType {
+    fn type_info() -> PgTypeInfo {
+        V::array_type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        V::array_compatible(ty)
+    }
+}
+
+impl<'a, 'q, V> Encode<'q, Postgres> for UnnestArray<'a, V>
+    where V: Encode<'q, Postgres> + Type<Postgres> {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&[&V] as Encode<'q, Postgres>>::encode_by_ref(&self.0.as_slice(), buf)
+    }
+}
@@ -0,0 +1,141 @@
+// The columns of flattened fields checked at compile time against a schema file, for
+// `#[cherry(verify)]`. The derive only sees the fields of the struct it's applied to, the columns
+// of a flattened struct are named by the constant the derive generates on it, and compared to the
+// schema columns in a constant evaluation, which fails the build when one is missing.
+
+// The columns of a struct, the `__CHERRY_COLUMNS` constant generated by the derive.
+pub enum Column {
+    Name(&'static str),
+    Flatten(&'static str, &'static [Column]), // prefix and columns of a flattened field
+}
+
+// The prefixes of the flattened fields, the innermost first.
+struct Prefix<'p> {
+    prefix: &'static str,
+    outer: Option<&'p Prefix<'p>>,
+}
+
+// Panics with the first column of `columns`, with `prefix` prepended, not in `schema`.
+pub const fn flattened(schema: &[&str], prefix: &'static str, columns: &[Column], table: &str, path: &str) {
+    check(schema, &Prefix { prefix, outer: None }, columns, table, path);
+}
+
+const fn check(schema: &[&str], prefix: &Prefix, columns: &[Column], table: &str, path: &str) {
+    let mut i = 0;
+    while i < columns.len() {
+        match &columns[i] {
+            Column::Name(column) => if !contains(schema, prefix, column) {
+                let message = Message::new()
+                    .push("Column `").prefix(prefix).push(column)
+                    .push("` of the flattened field not found in table `").push(table)
+                    .push("` of `").push(path).push("`.");
+                panic!("{}", message.as_str());
+            },
+            Column::Flatten(inner, columns) => check(schema, &Prefix { prefix: inner, outer: Some(prefix) }, columns, table, path),
+        }
+        i += 1;
+    }
+}
+
+const fn contains(schema: &[&str], prefix: &Prefix, column: &str) -> bool {
+    let mut i = 0;
+    while i < schema.len() {
+        if is_named(schema[i].as_bytes(), prefix, column.as_bytes()) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+// `name` is the prefixes, the outermost first, followed by `column`. Case insensitive.
+const fn is_named(name: &[u8], prefix: &Prefix, column: &[u8]) -> bool {
+    let mut rest = match strip_suffix(name, column) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let mut prefix = Some(prefix);
+    while let Some(p) = prefix {
+        rest = match strip_suffix(rest, p.prefix.as_bytes()) {
+            Some(rest) => rest,
+            None => return false,
+        };
+        prefix = p.outer;
+    }
+    rest.is_empty()
+}
+
+const fn strip_suffix<'s>(s: &'s [u8], suffix: &[u8]) -> Option<&'s [u8]> {
+    if s.len() < suffix.len() {
+        return None;
+    }
+    let (rest, end) = s.split_at(s.len() - suffix.len());
+    let mut i = 0;
+    while i < end.len() {
+        if !end[i].eq_ignore_ascii_case(&suffix[i]) {
+            return None;
+        }
+        i += 1;
+    }
+    Some(rest)
+}
+
+// A panic message built without allocation, cut at the capacity.
+struct Message {
+    bytes: [u8; 256],
+    len: usize,
+}
+
+impl Message {
+
+    const fn new() -> Self {
+        Self { bytes: [0; 256], len: 0 }
+    }
+
+    const fn push(mut self, s: &str) -> Self {
+        let s = s.as_bytes();
+        let mut i = 0;
+        while i < s.len() && self.len < self.bytes.len() {
+            self.bytes[self.len] = s[i];
+            self.len += 1;
+            i += 1;
+        }
+        self
+    }
+
+    // the outermost first
+    const fn prefix(self, prefix: &Prefix) -> Self {
+        let message = match prefix.outer {
+            Some(outer) => self.prefix(outer),
+            None => self,
+        };
+        message.push(prefix.prefix)
+    }
+
+    const fn as_str(&self) -> &str {
+        match std::str::from_utf8(self.bytes.split_at(self.len).0) {
+            Ok(s) => s,
+            Err(_) => "Column of the flattened field not found in the schema file.",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GEO: &[Column] = &[Column::Name("lat"), Column::Name("lng")];
+    const ADDRESS: &[Column] = &[Column::Name("city"), Column::Flatten("geo_", GEO)];
+
+    #[test]
+    fn test_flattened() {
+        flattened(&["id", "addr_city", "addr_geo_lat", "addr_geo_lng"], "addr_", ADDRESS, "shop", "schema.sql");
+        flattened(&["city", "geo_lat", "geo_lng"], "", ADDRESS, "shop", "schema.sql");
+    }
+
+    #[test]
+    #[should_panic(expected = "Column `addr_geo_lng` of the flattened field not found in table `shop` of `schema.sql`.")]
+    fn test_flattened_missing() {
+        flattened(&["id", "addr_city", "addr_geo_lat", "geo_lng"], "addr_", ADDRESS, "shop", "schema.sql");
+    }
+}
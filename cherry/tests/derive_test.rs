@@ -1,4 +1,5 @@
-use cherry::Cherry;
+use cherry::{Cherry, CherryEnum, QueryExecutor};
+use cherry::clause::{End, SelectColumn, Where};
 use cherry::sqlite::SqlitePool;
 use cherry::sqlx::{Arguments, Executor, Sqlite};
 use cherry::sqlx::sqlite::SqliteArguments;
@@ -34,3 +35,228 @@ async fn test_correct() {
         .execute(&pool).await.unwrap();
     assert_eq!(1, a.rows_affected());
 }
+
+#[derive(Cherry, Debug, PartialEq)]
+#[cherry(table = "user", database = "sqlite", verify = "tests/migrations.sql")]
+struct Person {
+    id: u32,
+    #[cherry(column = "name")]
+    full_name: String,
+    r#age: u8,
+}
+
+#[async_std::test]
+async fn test_column_rename() {
+    assert_eq!(vec![("id", "id"), ("full_name", "name"), ("age", "age")], <Person as Cherry<Sqlite>>::columns());
+
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let person = Person { id: 1, full_name: "Joe".into(), age: 30 };
+    person.insert().execute(&pool).await.unwrap();
+
+    let result = Person::select().and_eq("name", "Joe").one(&pool).await.unwrap();
+    assert_eq!(Some(person), result);
+}
+
+#[async_std::test]
+async fn test_typed_columns() {
+    assert_eq!("name", Person::FULL_NAME.name());
+    assert_eq!("age", Person::AGE.name());
+
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let persons = vec![
+        Person { id: 1, full_name: "Joe".into(), age: 30 },
+        Person { id: 2, full_name: "Ann".into(), age: 17 },
+    ];
+    Person::insert_bulk(&persons).execute(&pool).await.unwrap();
+
+    let result = Person::select()
+        .and_ge(Person::AGE, 18u8)
+        .and_in(Person::FULL_NAME, ["Joe", "Ann"])
+        .order_by_desc(Person::ID)
+        .all(&pool).await.unwrap();
+    assert_eq!(&persons[..1], &result);
+
+    let name = String::from("Ann");
+    let result = Person::select().and_eq(Person::FULL_NAME, &name).one(&pool).await.unwrap();
+    assert_eq!(Some(&persons[1]), result.as_ref());
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite", rename_all = "camelCase")]
+struct Account {
+    account_id: u32,
+    #[cherry(column = "DisplayName")]
+    display_name: String,
+    created_by: String,
+}
+
+#[test]
+fn test_rename_all() {
+    assert_eq!(
+        vec![("account_id", "accountId"), ("display_name", "DisplayName"), ("created_by", "createdBy")],
+        <Account as Cherry<Sqlite>>::columns()
+    );
+}
+
+#[derive(Cherry, Debug, PartialEq)]
+#[cherry(table = "user", database = "sqlite")]
+struct Member {
+    id: u32,
+    name: String,
+    #[cherry(default)]
+    age: u8,
+    #[cherry(skip)]
+    cached: Option<String>,
+}
+
+#[async_std::test]
+async fn test_skip_and_default() {
+    assert_eq!(vec![("id", "id"), ("name", "name"), ("age", "age")], <Member as Cherry<Sqlite>>::columns());
+
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let member = Member { id: 1, name: "Joe".into(), age: 30, cached: Some("cache".into()) };
+    member.insert().execute(&pool).await.unwrap();
+
+    let result = Member::select().column("id").column("name").and_eq("id", 1).one(&pool).await.unwrap();
+    assert_eq!(Some(Member { id: 1, name: "Joe".into(), age: 0, cached: None }), result);
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Enrollment {
+    #[cherry(primary_key)]
+    student_id: u32,
+    #[cherry(primary_key, column = "course")]
+    course_id: u32,
+    grade: u8,
+}
+
+#[async_std::test]
+async fn test_primary_key() {
+    assert_eq!(&["student_id", "course"], <Enrollment as Cherry<Sqlite>>::primary_key());
+    assert!(<User as Cherry<Sqlite>>::primary_key().is_empty());
+
+    let enrollment = Enrollment { student_id: 1, course_id: 2, grade: 90 };
+    let mut arguments = SqliteArguments::default();
+    enrollment.primary_key_arguments(&mut arguments);
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let (key,): (String,) = sqlx::query_as_with("SELECT ? || '-' || ?", arguments)
+        .fetch_one(&pool).await.unwrap();
+    assert_eq!("1-2", key);
+}
+
+#[derive(Cherry, Debug, PartialEq)]
+#[cherry(database = "sqlite", verify = false)]
+struct Address {
+    street: String,
+    city: String,
+}
+
+#[derive(Cherry, Debug, PartialEq)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+struct Shop {
+    #[cherry(primary_key)]
+    id: u32,
+    name: String,
+    #[cherry(flatten, prefix = "addr_")]
+    address: Address,
+}
+
+#[async_std::test]
+async fn test_flatten() {
+    assert_eq!(
+        vec![("id", "id"), ("name", "name"), ("street", "addr_street"), ("city", "addr_city")],
+        <Shop as Cherry<Sqlite>>::columns()
+    );
+
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let shop = Shop {
+        id: 1,
+        name: "Cherry".into(),
+        address: Address { street: "Main street".into(), city: "Springfield".into() },
+    };
+    shop.insert().execute(&pool).await.unwrap();
+
+    let result = Shop::select().and_eq("addr_city", "Springfield").one(&pool).await.unwrap();
+    assert_eq!(Some(shop), result);
+}
+
+#[derive(CherryEnum, Debug, PartialEq)]
+#[cherry(rename_all = "snake_case")]
+enum Status {
+    Todo,
+    InProgress,
+    #[cherry(rename = "finished")]
+    Done,
+}
+
+#[derive(CherryEnum, Debug, PartialEq)]
+#[cherry(repr = "i32")]
+enum Priority {
+    Low = 1,
+    High = 10,
+}
+
+#[derive(Cherry, Debug, PartialEq)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+struct Task {
+    id: u32,
+    status: Status,
+    priority: Priority,
+}
+
+#[async_std::test]
+async fn test_enum() {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let tasks = vec![
+        Task { id: 1, status: Status::InProgress, priority: Priority::High },
+        Task { id: 2, status: Status::Done, priority: Priority::Low },
+    ];
+    Task::insert_bulk(&tasks).execute(&pool).await.unwrap();
+
+    let raw: Vec<(String, i32)> = sqlx::query_as("SELECT status, priority FROM task ORDER BY id")
+        .fetch_all(&pool).await.unwrap();
+    assert_eq!(vec![("in_progress".to_string(), 10), ("finished".to_string(), 1)], raw);
+
+    let result = Task::select().and_in("status", [Status::Todo, Status::Done]).all(&pool).await.unwrap();
+    assert_eq!(&tasks[1..], &result);
+    let result = Task::select().and_eq("priority", Priority::High).all(&pool).await.unwrap();
+    assert_eq!(&tasks[..1], &result);
+}
+
+#[derive(Cherry, Debug, PartialEq)]
+#[cherry(table = "user", database = "sqlite")]
+struct Record<A> where A: Clone {
+    id: u32,
+    name: String,
+    age: A,
+}
+
+#[derive(Cherry, Debug, PartialEq)]
+#[cherry(table = "user", database = "sqlite")]
+struct Row(
+    #[cherry(column = "id")] u32,
+    #[cherry(column = "name")] String,
+    #[cherry(column = "age")] u8,
+);
+
+#[async_std::test]
+async fn test_generic_and_tuple_struct() {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let record = Record { id: 1, name: "Joe".to_string(), age: 30i64 };
+    record.insert().execute(&pool).await.unwrap();
+
+    let result = Record::<i64>::select().and_eq(Record::<i64>::AGE, 30).one(&pool).await.unwrap();
+    assert_eq!(Some(record), result);
+
+    assert_eq!(vec![("0", "id"), ("1", "name"), ("2", "age")], <Row as Cherry<Sqlite>>::columns());
+    Row(2, "Ann".into(), 17).insert().execute(&pool).await.unwrap();
+    let result = Row::select().and_eq(Row::NAME, "Ann").one(&pool).await.unwrap();
+    assert_eq!(Some(Row(2, "Ann".into(), 17)), result);
+}
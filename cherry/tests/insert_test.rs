@@ -1,10 +1,12 @@
-use chrono::NaiveDate;
+use std::cell::Cell;
+
+use chrono::{NaiveDate, NaiveDateTime};
 use sqlx::{Executor, Row};
 
-use cherry::Cherry;
-use cherry::clause::{InsertConflict, UpdateSet};
+use cherry::{Cherry, PrimaryKey, QueryError, Tracked};
+use cherry::clause::{InsertConflict, UpdateSet, Where};
 use cherry::QueryExecutor;
-use cherry::sqlite::SqlitePool;
+use cherry::sqlite::{Sqlite, SqlitePool};
 use cherry::sqlx::types::Json;
 
 async fn init() -> SqlitePool {
@@ -26,6 +28,112 @@ async fn test_insert_one() {
     assert_eq!(&user, &result);
 }
 
+#[async_std::test]
+async fn test_insert_chunked() {
+    let pool = init().await;
+    // 3 columns * 20000 rows, over the sqlite limit of 32766 parameters
+    let users = (0..20000).map(|id| User { id, name: format!("user {}", id), age: 25 })
+        .collect::<Vec<User>>();
+    let r = User::insert_bulk(&users).execute_chunked_tx(&pool).await.unwrap();
+    assert_eq!(20000, r.rows_affected());
+
+    let r = User::insert_bulk(&users[..10]).ignore_on_conflict().execute_chunked(&pool).await.unwrap();
+    assert_eq!(0, r.rows_affected());
+
+    // nothing to insert, nothing executed
+    assert_eq!(0, User::insert_bulk(&[]).execute_chunked(&pool).await.unwrap().rows_affected());
+    assert_eq!(0, User::insert_bulk(&[]).execute_chunked_tx(&pool).await.unwrap().rows_affected());
+
+    let e = User::insert_bulk(&users).execute(&pool).await.unwrap_err();
+    assert!(matches!(e.downcast_ref::<QueryError>(), Some(QueryError::TooManyParameters(60000))));
+
+    // the last chunk fails on the key of the first one
+    let users = (20000..40000).chain([20000]).map(|id| User { id, name: String::new(), age: 25 })
+        .collect::<Vec<User>>();
+    assert!(User::insert_bulk(&users).execute_chunked_tx(&pool).await.is_err());
+    assert_eq!(None, User::select().and_eq("id", 20000).one(&pool).await.unwrap());
+    assert!(User::insert_bulk(&users).execute_chunked(&pool).await.is_err());
+    assert!(User::select().and_eq("id", 20000).one(&pool).await.unwrap().is_some());
+}
+
+#[async_std::test]
+async fn test_insert_not_sync() {
+    let pool = init().await;
+    let visitor = Visitor { id: 1, name: "test_insert_not_sync".into(), age: 25, visits: Cell::new(1) };
+    visitor.insert().execute(&pool).await.unwrap();
+    visitor.visits.set(2);
+    let users = vec![Visitor { id: 2, ..Default::default() }, Visitor { id: 3, ..Default::default() }];
+    assert_eq!(2, Visitor::insert_bulk(&users).execute(&pool).await.unwrap().rows_affected());
+    assert_eq!(3, User::select().all(&pool).await.unwrap().len());
+}
+
+#[async_std::test]
+async fn test_insert_generated() {
+    let pool = init().await;
+    let post = Post { id: 0, title: "test_insert_generated".into(), created_at: String::new() };
+    assert_eq!(vec![("title", "title")], <Post as Cherry<Sqlite>>::insert_columns());
+    post.insert().execute(&pool).await.unwrap();
+    post.insert().execute(&pool).await.unwrap();
+
+    let posts = Post::select().all(&pool).await.unwrap();
+    assert_eq!(vec![1, 2], posts.iter().map(|p| p.id).collect::<Vec<i64>>());
+    assert!(posts.iter().all(|p| !p.created_at.is_empty()));
+
+    // the generated columns are never updated, `Tracked::set` doesn't accept them
+    let mut post = Tracked::new(Post::find_or_fail(1, &pool).await.unwrap());
+    post.set(Post::TITLE, "Updated".into());
+    post.update().execute(&pool).await.unwrap();
+    let updated = Post::find_or_fail(1, &pool).await.unwrap();
+    assert_eq!(("Updated", posts[0].created_at.as_str()), (updated.title.as_str(), updated.created_at.as_str()));
+}
+
+#[async_std::test]
+async fn test_timestamps() {
+    let pool = init().await;
+    let past = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let article = Article { id: 1, title: "Draft".into(), created_at: past, updated_at: past };
+    article.insert().execute(&pool).await.unwrap();
+    let inserted = Article::find_or_fail(1, &pool).await.unwrap();
+    assert!(inserted.created_at > past);
+    assert!(inserted.updated_at > past);
+
+    let reset = "UPDATE article SET updated_at = '2000-01-01 00:00:00'";
+    sqlx::query(reset).execute(&pool).await.unwrap();
+    Article::update().set("title", "Final").and_eq("id", 1).execute(&pool).await.unwrap();
+    let updated = Article::find_or_fail(1, &pool).await.unwrap();
+    assert!(updated.updated_at > inserted.updated_at);
+    assert_eq!(inserted.created_at, updated.created_at);
+
+    // nothing to set, nothing executed
+    sqlx::query(reset).execute(&pool).await.unwrap();
+    assert_eq!(0, Article::update().and_eq("id", 1).execute(&pool).await.unwrap().rows_affected());
+
+    // the created_at of the saved value is ignored
+    article.save().execute(&pool).await.unwrap();
+    let saved = Article::find_or_fail(1, &pool).await.unwrap();
+    assert_eq!(inserted.created_at, saved.created_at);
+    assert!(saved.updated_at > past);
+
+    sqlx::query(reset).execute(&pool).await.unwrap();
+    article.insert().update_on_conflict().conflict_column("id").set_column("title").execute(&pool).await.unwrap();
+    assert!(Article::find_or_fail(1, &pool).await.unwrap().updated_at > past);
+}
+
+#[async_std::test]
+async fn test_insert_empty() {
+    let pool = init().await;
+    // no row, nothing executed
+    let r = User::insert_bulk(&[]).execute(&pool).await.unwrap();
+    assert_eq!(0, r.rows_affected());
+    let r = User::insert_bulk(&[]).ignore_on_conflict().execute(&pool).await.unwrap();
+    assert_eq!(0, r.rows_affected());
+
+    // an empty list matches nothing, the other way round with NOT IN
+    User { id: 1, name: "test_insert_empty".into(), age: 25 }.insert().execute(&pool).await.unwrap();
+    assert!(User::select().and_in("id", Vec::<u32>::new()).all(&pool).await.unwrap().is_empty());
+    assert_eq!(1, User::select().and_not_in("id", Vec::<u32>::new()).all(&pool).await.unwrap().len());
+}
+
 #[async_std::test]
 async fn test_insert_ignore() {
 
@@ -33,7 +141,7 @@ async fn test_insert_ignore() {
 
 #[async_std::test]
 async fn test_insert_update() {
-    User::insert_bulk(&[]).update_on_conflict().conflict_column("id").set_column("name").set_column("age")
+    User::insert_bulk(&[]).update_on_conflict().conflict_column("id").set_column("name").set_column("age");
 }
 
 #[async_std::test]
@@ -50,6 +158,19 @@ struct User {
     age: u8,
 }
 
+// `Cell` is not `Sync`, the query borrows the inserted fields only
+#[derive(Debug, Default, Cherry)]
+#[cherry(database = "sqlite", table = "user")]
+struct Visitor {
+    id: u32,
+    name: String,
+    age: u8,
+    #[cherry(skip)]
+    visits: Cell<u32>,
+}
+
+// only derived, the json and chrono fields have to compile
+#[allow(dead_code)]
 #[derive(Debug, Eq, PartialEq, Cherry)]
 #[cherry(database = "sqlite")]
 struct Book {
@@ -60,6 +181,28 @@ struct Book {
     published_date: NaiveDate,
 }
 
+#[derive(Debug, Cherry)]
+#[cherry(database = "sqlite")]
+struct Post {
+    #[cherry(primary_key, auto_increment)]
+    id: i64,
+    title: String,
+    #[cherry(db_default)]
+    created_at: String,
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "sqlite")]
+struct Article {
+    #[cherry(primary_key)]
+    id: u32,
+    title: String,
+    #[cherry(created_at)]
+    created_at: NaiveDateTime,
+    #[cherry(updated_at)]
+    updated_at: NaiveDateTime,
+}
+
 #[test]
 fn check_type() {
 
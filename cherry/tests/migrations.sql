@@ -14,4 +14,115 @@ create table book (
     `edition` int unsigned not null,
     `published_date` text not null,
     primary key (`id`)
-) without rowid;
\ No newline at end of file
+) without rowid;
+
+drop table if exists post;
+create table post (
+    `id` integer primary key autoincrement,
+    `title` text not null,
+    `created_at` text not null default current_timestamp
+);
+
+drop table if exists shop;
+create table shop (
+    `id` int unsigned not null,
+    `name` text not null,
+    `addr_street` text not null,
+    `addr_city` text not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists task;
+create table task (
+    `id` int unsigned not null,
+    `status` text not null,
+    `priority` int not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists enrollment;
+create table enrollment (
+    `student_id` int unsigned not null,
+    `course` int unsigned not null,
+    `grade` int unsigned not null,
+    primary key (`student_id`, `course`)
+) without rowid;
+
+drop table if exists document;
+create table document (
+    `id` int unsigned not null,
+    `title` text not null,
+    `version` int unsigned not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists article;
+create table article (
+    `id` int unsigned not null,
+    `title` text not null,
+    `created_at` text not null,
+    `updated_at` text not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists comment;
+create table comment (
+    `id` int unsigned not null,
+    `body` text not null,
+    `deleted_at` text,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists project;
+create table project (
+    `tenant_id` int unsigned not null,
+    `id` int unsigned not null,
+    `name` text not null,
+    `archived` boolean not null,
+    primary key (`tenant_id`, `id`)
+) without rowid;
+
+drop table if exists author;
+create table author (
+    `id` int unsigned not null,
+    `name` text not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists novel;
+create table novel (
+    `id` int unsigned not null,
+    `author_id` int unsigned not null,
+    `title` text not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists chapter;
+create table chapter (
+    `id` int unsigned not null,
+    `novel_id` int unsigned not null,
+    `title` text not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists review;
+create table review (
+    `id` int unsigned not null,
+    `novel_id` int unsigned,
+    `text` text not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists genre;
+create table genre (
+    `id` int unsigned not null,
+    `name` text not null,
+    primary key (`id`)
+) without rowid;
+
+drop table if exists novel_genres;
+create table novel_genres (
+    `novel_id` int unsigned not null,
+    `genre_id` int unsigned not null,
+    primary key (`novel_id`, `genre_id`)
+) without rowid;
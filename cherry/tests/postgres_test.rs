@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use sqlx::Executor;
+
+use cherry::{Cherry, CherryEnum, QueryExecutor};
+use cherry::clause::{InsertConflict, UpdateSet, Where};
+use cherry::postgres::{PgPool, set_local_statement_timeout, Unnest};
+
+// A postgres server in `DATABASE_URL`, e.g. `postgres://postgres@127.0.0.1/postgres`.
+async fn init() -> PgPool {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL of a postgres server");
+    let pool = PgPool::connect(&url).await.unwrap();
+    pool.execute("create table if not exists account (id int4 not null primary key, name text not null)")
+        .await.unwrap();
+    pool.execute("create table if not exists ticket (id int4 not null primary key, status text not null, priority int4 not null)")
+        .await.unwrap();
+    // the statement timeout of the insert
+    pool.execute("create table if not exists timeout_log (id int4 not null primary key, \
+        setting text not null default current_setting('statement_timeout'))").await.unwrap();
+    pool
+}
+
+async fn statement_timeout(conn: &mut sqlx::PgConnection) -> String {
+    sqlx::query_scalar("show statement_timeout").fetch_one(conn).await.unwrap()
+}
+
+#[async_std::test]
+#[ignore = "needs a postgres server in DATABASE_URL"]
+async fn test_local_statement_timeout() {
+    let pool = init().await;
+    let mut tx = pool.begin().await.unwrap();
+    set_local_statement_timeout(&mut tx, Duration::from_millis(1500)).await.unwrap();
+    assert_eq!("1500ms", statement_timeout(&mut tx).await);
+    // rounded up, zero would disable it
+    set_local_statement_timeout(&mut tx, Duration::from_micros(500)).await.unwrap();
+    assert_eq!("1ms", statement_timeout(&mut tx).await);
+    tx.rollback().await.unwrap();
+
+    // only until the end of the transaction
+    let mut tx = pool.begin().await.unwrap();
+    assert_eq!("0", statement_timeout(&mut tx).await);
+}
+
+#[async_std::test]
+#[ignore = "needs a postgres server in DATABASE_URL"]
+async fn test_chunked_statement_timeout() {
+    let pool = init().await;
+    pool.execute("delete from timeout_log").await.unwrap();
+    let logs = [TimeoutLog { id: 1, setting: String::new() }];
+    TimeoutLog::insert_unnest(&logs).timeout(Duration::from_millis(1500)).execute_chunked_tx(&pool).await.unwrap();
+    // out of a transaction begun by cherry
+    let logs = [TimeoutLog { id: 2, setting: String::new() }];
+    TimeoutLog::insert_unnest(&logs).timeout(Duration::from_millis(1500)).execute_chunked(&pool).await.unwrap();
+
+    let settings: Vec<String> = sqlx::query_scalar("select setting from timeout_log order by id")
+        .fetch_all(&pool).await.unwrap();
+    assert_eq!(vec!["1500ms", "0"], settings);
+    pool.execute("delete from timeout_log").await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs a postgres server in DATABASE_URL"]
+async fn test_insert_unnest() {
+    let pool = init().await;
+    let mut tx = pool.begin().await.unwrap();
+    let accounts = (0..40000).map(|id| Account { id, name: format!("Account {}", id) })
+        .collect::<Vec<Account>>();
+    // a single statement of two arrays, far over the parameter limit with VALUES
+    let result = Account::insert_unnest(&accounts).execute(&mut *tx).await.unwrap();
+    assert_eq!(40000, result.rows_affected());
+
+    let name: String = sqlx::query_scalar("select name from account where id = 39999")
+        .fetch_one(&mut *tx).await.unwrap();
+    assert_eq!("Account 39999", name);
+    tx.rollback().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs a postgres server in DATABASE_URL"]
+async fn test_insert_unnest_update_on_conflict() {
+    let pool = init().await;
+    let mut tx = pool.begin().await.unwrap();
+    let accounts = vec![Account { id: 1, name: "One".into() }, Account { id: 2, name: "Two".into() }];
+    Account::insert_unnest(&accounts).execute(&mut *tx).await.unwrap();
+
+    // the SET value is numbered after the arrays
+    let accounts = vec![Account { id: 2, name: "Second".into() }, Account { id: 3, name: "Three".into() }];
+    Account::insert_unnest(&accounts)
+        .update_on_conflict()
+        .conflict_column("id")
+        .set("name", "Updated")
+        .execute(&mut *tx).await.unwrap();
+
+    let names: Vec<(i32, String)> = sqlx::query_as("select id, name from account order by id")
+        .fetch_all(&mut *tx).await.unwrap();
+    assert_eq!(vec![(1, "One".into()), (2, "Updated".into()), (3, "Three".into())], names);
+    let account = Account::select().and_eq("id", 3).one(&mut *tx).await.unwrap().unwrap();
+    assert_eq!("Three", account.name);
+    tx.rollback().await.unwrap();
+}
+
+#[async_std::test]
+#[ignore = "needs a postgres server in DATABASE_URL"]
+async fn test_insert_unnest_enum() {
+    let pool = init().await;
+    let mut tx = pool.begin().await.unwrap();
+    let tickets = vec![
+        Ticket { id: 1, status: Status::Todo, priority: Priority::High },
+        Ticket { id: 2, status: Status::Done, priority: Priority::Low },
+    ];
+    Ticket::insert_unnest(&tickets).execute(&mut *tx).await.unwrap();
+
+    let raw: Vec<(String, i32)> = sqlx::query_as("select status, priority from ticket order by id")
+        .fetch_all(&mut *tx).await.unwrap();
+    assert_eq!(vec![("todo".to_string(), 10), ("done".to_string(), 1)], raw);
+
+    let done: Vec<i32> = sqlx::query_scalar("select id from ticket where status = any($1)")
+        .bind(vec![Status::Done]).fetch_all(&mut *tx).await.unwrap();
+    assert_eq!(vec![2], done);
+    tx.rollback().await.unwrap();
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "postgres", unnest)]
+struct Account {
+    id: i32,
+    name: String,
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "postgres", unnest)]
+struct TimeoutLog {
+    id: i32,
+    #[cherry(generated)]
+    setting: String,
+}
+
+#[derive(CherryEnum, Debug, PartialEq)]
+#[cherry(database = "postgres", rename_all = "snake_case")]
+enum Status {
+    Todo,
+    Done,
+}
+
+#[derive(CherryEnum, Debug, PartialEq)]
+#[cherry(database = "postgres", repr = "i32")]
+enum Priority {
+    Low = 1,
+    High = 10,
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "postgres", unnest)]
+struct Ticket {
+    id: i32,
+    status: Status,
+    priority: Priority,
+}
@@ -0,0 +1,249 @@
+use sqlx::Executor;
+
+use cherry::{Cherry, PrimaryKey, QueryError, Tracked};
+use cherry::QueryExecutor;
+use cherry::sqlite::SqlitePool;
+
+async fn init() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let users = vec![
+        User { id: 1, name: "Joe".into(), age: 25 },
+        User { id: 2, name: "Ann".into(), age: 17 },
+        User { id: 3, name: "Bob".into(), age: 40 },
+    ];
+    User::insert_bulk(&users).execute(&pool).await.unwrap();
+    let enrollments = vec![
+        Enrollment { student_id: 1, course_id: 1, grade: 90 },
+        Enrollment { student_id: 1, course_id: 2, grade: 80 },
+        Enrollment { student_id: 2, course_id: 1, grade: 70 },
+    ];
+    Enrollment::insert_bulk(&enrollments).execute(&pool).await.unwrap();
+    Document { id: 1, title: "Draft".into(), version: 1 }.insert().execute(&pool).await.unwrap();
+    pool
+}
+
+#[async_std::test]
+async fn test_find() {
+    let pool = init().await;
+    let user = User::find(2).one(&pool).await.unwrap();
+    assert_eq!(Some(User { id: 2, name: "Ann".into(), age: 17 }), user);
+    assert_eq!(None, User::find(4).one(&pool).await.unwrap());
+
+    let enrollment = Enrollment::find((1, 2)).one(&pool).await.unwrap();
+    assert_eq!(Some(Enrollment { student_id: 1, course_id: 2, grade: 80 }), enrollment);
+}
+
+#[async_std::test]
+async fn test_find_many() {
+    let pool = init().await;
+    let users = User::find_many([3, 1, 4]).all(&pool).await.unwrap();
+    let mut ids = users.iter().map(|u| u.id).collect::<Vec<u32>>();
+    ids.sort();
+    assert_eq!(vec![1, 3], ids);
+    assert!(User::find_many([]).all(&pool).await.unwrap().is_empty());
+
+    let enrollments = Enrollment::find_many([(1, 1), (2, 1), (2, 2)]).all(&pool).await.unwrap();
+    let mut grades = enrollments.iter().map(|e| e.grade).collect::<Vec<u8>>();
+    grades.sort();
+    assert_eq!(vec![70, 90], grades);
+}
+
+#[async_std::test]
+async fn test_find_or_fail() {
+    let pool = init().await;
+    assert_eq!("Joe", User::find_or_fail(1, &pool).await.unwrap().name);
+
+    let err = User::find_or_fail(4, &pool).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::NotFound("user"))));
+}
+
+#[async_std::test]
+async fn test_update_by_pk() {
+    let pool = init().await;
+    let mut user = User::find_or_fail(2, &pool).await.unwrap();
+    user.name = "Anna".into();
+    user.age = 18;
+    assert_eq!(1, user.update_by_pk().execute(&pool).await.unwrap().rows_affected());
+    assert_eq!(Some(user), User::find(2).one(&pool).await.unwrap());
+    assert_eq!("Joe", User::find_or_fail(1, &pool).await.unwrap().name);
+
+    let mut enrollment = Enrollment { student_id: 1, course_id: 2, grade: 85 };
+    enrollment.update_by_pk().execute(&pool).await.unwrap();
+    assert_eq!(85, Enrollment::find_or_fail((1, 2), &pool).await.unwrap().grade);
+    assert_eq!(90, Enrollment::find_or_fail((1, 1), &pool).await.unwrap().grade);
+}
+
+#[async_std::test]
+async fn test_delete_by_pk() {
+    let pool = init().await;
+    let enrollment = Enrollment { student_id: 1, course_id: 1, grade: 0 };
+    assert_eq!(1, enrollment.delete_by_pk().execute(&pool).await.unwrap().rows_affected());
+    assert_eq!(None, Enrollment::find((1, 1)).one(&pool).await.unwrap());
+    assert!(Enrollment::find((1, 2)).one(&pool).await.unwrap().is_some());
+}
+
+#[async_std::test]
+async fn test_save() {
+    let pool = init().await;
+    let user = User { id: 4, name: "Eve".into(), age: 33 };
+    user.save().execute(&pool).await.unwrap();
+    assert_eq!(Some(user), User::find(4).one(&pool).await.unwrap());
+
+    let user = User { id: 1, name: "Joseph".into(), age: 26 };
+    user.save().execute(&pool).await.unwrap();
+    assert_eq!(Some(user), User::find(1).one(&pool).await.unwrap());
+    assert_eq!(4, User::select().all(&pool).await.unwrap().len());
+}
+
+#[async_std::test]
+async fn test_reload() {
+    let pool = init().await;
+    let mut user = User::find_or_fail(1, &pool).await.unwrap();
+    sqlx::query("UPDATE user SET age = age + 1").execute(&pool).await.unwrap();
+    user.reload(&pool).await.unwrap();
+    assert_eq!(26, user.age);
+
+    user.delete_by_pk().execute(&pool).await.unwrap();
+    let err = user.reload(&pool).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::NotFound("user"))));
+}
+
+#[async_std::test]
+async fn test_reload_all() {
+    let pool = init().await;
+    let mut users = User::find_many([3, 1]).all(&pool).await.unwrap();
+    sqlx::query("UPDATE user SET name = name || '!'").execute(&pool).await.unwrap();
+    User::reload_all(&mut users, &pool).await.unwrap();
+    let mut names = users.iter().map(|u| u.name.as_str()).collect::<Vec<&str>>();
+    names.sort();
+    assert_eq!(vec!["Bob!", "Joe!"], names);
+
+    User::find_or_fail(3, &pool).await.unwrap().delete_by_pk().execute(&pool).await.unwrap();
+    let err = User::reload_all(&mut users, &pool).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::NotFound("user"))));
+
+    let mut enrollments = Enrollment::find_many([(1, 1), (2, 1)]).all(&pool).await.unwrap();
+    sqlx::query("UPDATE enrollment SET grade = 100").execute(&pool).await.unwrap();
+    Enrollment::reload_all(&mut enrollments, &pool).await.unwrap();
+    assert!(enrollments.iter().all(|e| e.grade == 100));
+}
+
+#[async_std::test]
+async fn test_reload_all_duplicate_key() {
+    let pool = init().await;
+    let mut users = vec![
+        User::find_or_fail(1, &pool).await.unwrap(),
+        User::find_or_fail(3, &pool).await.unwrap(),
+        User::find_or_fail(1, &pool).await.unwrap(),
+    ];
+    sqlx::query("UPDATE user SET name = name || '!'").execute(&pool).await.unwrap();
+    let err = User::reload_all(&mut users, &pool).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::DuplicateKey("user"))));
+    // none is reloaded
+    assert_eq!(vec!["Joe", "Bob", "Joe"], users.iter().map(|u| u.name.as_str()).collect::<Vec<&str>>());
+
+    users.pop();
+    User::reload_all(&mut users, &pool).await.unwrap();
+    assert_eq!(vec!["Joe!", "Bob!"], users.iter().map(|u| u.name.as_str()).collect::<Vec<&str>>());
+}
+
+#[async_std::test]
+async fn test_tracked_update() {
+    let pool = init().await;
+    let mut user = Tracked::new(User::find_or_fail(1, &pool).await.unwrap());
+    sqlx::query("UPDATE user SET age = 50 WHERE id = 1").execute(&pool).await.unwrap();
+
+    user.set(User::NAME, "Joseph".to_string());
+    assert_eq!("Joseph", user.name);
+    assert_eq!(&["name"], user.changed());
+    assert_eq!(1, user.update().execute(&pool).await.unwrap().rows_affected());
+    assert!(!user.is_changed());
+    // the concurrent change of `age` is kept
+    assert_eq!(User { id: 1, name: "Joseph".into(), age: 50 }, User::find_or_fail(1, &pool).await.unwrap());
+
+    // nothing changed, nothing executed
+    assert_eq!(0, user.update().execute(&pool).await.unwrap().rows_affected());
+}
+
+#[async_std::test]
+async fn test_tracked_update_failed() {
+    let pool = init().await;
+    let mut document = Tracked::new(Document::find_or_fail(1, &pool).await.unwrap());
+    sqlx::query("UPDATE document SET version = version + 1 WHERE id = 1").execute(&pool).await.unwrap();
+
+    document.set(Document::TITLE, "Final".to_string());
+    let error = document.update().execute(&pool).await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<QueryError>(), Some(QueryError::StaleObject("document"))));
+    // kept to be updated again, with the version it was loaded with
+    assert_eq!(&["title"], document.changed());
+    assert_eq!(1, document.version);
+}
+
+#[async_std::test]
+async fn test_version() {
+    let pool = init().await;
+    let mut document = Document::find_or_fail(1, &pool).await.unwrap();
+    let mut stale = Document::find_or_fail(1, &pool).await.unwrap();
+
+    document.title = "Draft".into();
+    document.update_by_pk().execute(&pool).await.unwrap();
+    assert_eq!(2, document.version);
+    // updated again without reloading it
+    document.title = "Final".into();
+    document.update_by_pk().execute(&pool).await.unwrap();
+    assert_eq!(Document { id: 1, title: "Final".into(), version: 3 }, document);
+    assert_eq!(document, Document::find_or_fail(1, &pool).await.unwrap());
+
+    let error = stale.update_by_pk().execute(&pool).await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<QueryError>(), Some(QueryError::StaleObject("document"))));
+    let error = stale.delete_by_pk().execute(&pool).await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<QueryError>(), Some(QueryError::StaleObject("document"))));
+
+    let mut tracked = Tracked::new(document);
+    tracked.set(Document::TITLE, "Published".to_string());
+    tracked.update().execute(&pool).await.unwrap();
+    assert_eq!(4, tracked.version);
+    tracked.set(Document::TITLE, "Archived".to_string());
+    tracked.update().execute(&pool).await.unwrap();
+    assert_eq!(5, Document::find_or_fail(1, &pool).await.unwrap().version);
+    assert_eq!(*tracked, Document::find_or_fail(1, &pool).await.unwrap());
+
+    let error = Document { id: 1, title: "Saved".into(), version: 1 }.save().execute(&pool).await.unwrap_err();
+    assert!(matches!(error.downcast_ref::<QueryError>(), Some(QueryError::StaleObject("document"))));
+    assert_eq!("Archived", Document::find_or_fail(1, &pool).await.unwrap().title);
+    Document { id: 1, title: "Saved".into(), version: 5 }.save().execute(&pool).await.unwrap();
+    assert_eq!(Document { id: 1, title: "Saved".into(), version: 6 }, Document::find_or_fail(1, &pool).await.unwrap());
+
+    let document = Document::find_or_fail(1, &pool).await.unwrap();
+    assert_eq!(1, document.delete_by_pk().execute(&pool).await.unwrap().rows_affected());
+}
+
+#[derive(Debug, Cherry, PartialEq)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+struct User {
+    #[cherry(primary_key)]
+    id: u32,
+    name: String,
+    age: u8,
+}
+
+#[derive(Debug, Cherry, PartialEq)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+struct Enrollment {
+    #[cherry(primary_key)]
+    student_id: u32,
+    #[cherry(primary_key, column = "course")]
+    course_id: u32,
+    grade: u8,
+}
+
+#[derive(Debug, Cherry, PartialEq)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+struct Document {
+    #[cherry(primary_key)]
+    id: u32,
+    title: String,
+    #[cherry(version)]
+    version: u32,
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use sqlx::Executor;
+
+use cherry::{Cherry, PrimaryKey};
+use cherry::clause::{End, Where};
+use cherry::QueryExecutor;
+use cherry::sqlite::SqlitePool;
+
+async fn init() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let authors = vec![
+        Author { id: 1, name: "Austen".into() },
+        Author { id: 2, name: "Bronte".into() },
+        Author { id: 3, name: "Carroll".into() },
+    ];
+    Author::insert_bulk(&authors).execute(&pool).await.unwrap();
+    let novels = vec![
+        Novel { id: 1, author_id: 1, title: "Emma".into() },
+        Novel { id: 2, author_id: 1, title: "Persuasion".into() },
+        Novel { id: 3, author_id: 2, title: "Jane Eyre".into() },
+    ];
+    Novel::insert_bulk(&novels).execute(&pool).await.unwrap();
+    let chapters = vec![
+        Chapter { id: 1, novel_id: 1, title: "One".into() },
+        Chapter { id: 2, novel_id: 1, title: "Two".into() },
+        Chapter { id: 3, novel_id: 3, title: "Three".into() },
+    ];
+    Chapter::insert_bulk(&chapters).execute(&pool).await.unwrap();
+    let genres = vec![
+        Genre { id: 1, name: "Romance".into() },
+        Genre { id: 2, name: "Satire".into() },
+        Genre { id: 3, name: "Gothic".into() },
+    ];
+    Genre::insert_bulk(&genres).execute(&pool).await.unwrap();
+    pool
+}
+
+#[async_std::test]
+async fn test_has_many() {
+    let pool = init().await;
+    let author = Author { id: 1, name: "Austen".into() };
+    let novels = author.novels().order_by_desc("id").all(&pool).await.unwrap();
+    assert_eq!(vec![2, 1], novels.iter().map(|n| n.id).collect::<Vec<u32>>());
+
+    // further filtered
+    let novel = author.novels().and_eq("title", "Emma").one(&pool).await.unwrap();
+    assert_eq!(Some(1), novel.map(|n| n.id));
+
+    let author = Author { id: 3, name: "Carroll".into() };
+    assert!(author.novels().all(&pool).await.unwrap().is_empty());
+}
+
+#[async_std::test]
+async fn test_belongs_to() {
+    let pool = init().await;
+    let novel = Novel { id: 3, author_id: 2, title: "Jane Eyre".into() };
+    let author = novel.author().one(&pool).await.unwrap().unwrap();
+    assert_eq!("Bronte", author.name);
+
+    // named after `name`
+    let chapter = Chapter { id: 3, novel_id: 3, title: "Three".into() };
+    let book = chapter.book().one(&pool).await.unwrap().unwrap();
+    assert_eq!("Jane Eyre", book.title);
+
+    // a nullable foreign key
+    let review = Review { id: 1, novel_id: Some(1), text: "Witty".into() };
+    let novel = review.novel().unwrap().one(&pool).await.unwrap().unwrap();
+    assert_eq!("Emma", novel.title);
+    let review = Review { id: 2, novel_id: None, text: "Unread".into() };
+    assert!(review.novel().is_none());
+}
+
+#[async_std::test]
+async fn test_load_related() {
+    let pool = init().await;
+    let authors = Author::select().all(&pool).await.unwrap();
+    let novels = Author::load_related::<Novel>(&authors, &pool).await.unwrap();
+    let titles = novels.iter()
+        .map(|(id, novels)| (*id, novels.iter().map(|n| n.title.as_str()).collect()))
+        .collect::<HashMap<u32, Vec<&str>>>();
+    assert_eq!(HashMap::from([(1, vec!["Emma", "Persuasion"]), (2, vec!["Jane Eyre"])]), titles);
+
+    assert!(Author::load_related::<Novel>(&[], &pool).await.unwrap().is_empty());
+
+    // more keys than the parameters of one query
+    let authors = (1..=40000).map(|id| Author { id, name: "".into() }).collect::<Vec<Author>>();
+    let novels = Author::load_related::<Novel>(&authors, &pool).await.unwrap();
+    assert_eq!(2, novels.len());
+    assert_eq!(2, novels[&1].len());
+}
+
+#[async_std::test]
+async fn test_with_related() {
+    let pool = init().await;
+    let authors = Author::select().order_by_desc("id").all(&pool).await.unwrap();
+    let authors = Author::with_related::<Novel>(authors, &pool).await.unwrap();
+    let counts = authors.iter().map(|(a, novels)| (a.id, novels.len())).collect::<Vec<(u32, usize)>>();
+    assert_eq!(vec![(3, 0), (2, 1), (1, 2)], counts);
+
+    // the same author twice gets the novels twice
+    let twice = vec![Author { id: 1, name: "".into() }, Author { id: 1, name: "".into() }];
+    let twice = Author::with_related::<Novel>(twice, &pool).await.unwrap();
+    assert_eq!(vec![2, 2], twice.iter().map(|(_, novels)| novels.len()).collect::<Vec<usize>>());
+
+    // nested, the chapters of the novels of every author
+    let chapters = Novel::load_related::<Chapter>(authors.iter().flat_map(|(_, novels)| novels), &pool)
+        .await.unwrap();
+    assert_eq!(2, chapters[&1].len());
+    assert_eq!(1, chapters[&3].len());
+    assert!(!chapters.contains_key(&2));
+}
+
+#[async_std::test]
+async fn test_many_to_many() {
+    let pool = init().await;
+    let novel = Novel { id: 1, author_id: 1, title: "Emma".into() };
+    let genres = |novel: &Novel| {
+        let genres = novel.genres().order_by_asc("id").all(&pool);
+        async move { genres.await.unwrap().into_iter().map(|g| g.id).collect::<Vec<u32>>() }
+    };
+
+    let result = novel.attach_genres([1, 2]).execute(&pool).await.unwrap();
+    assert_eq!(2, result.rows_affected());
+    // the pairs already there are ignored
+    let result = novel.attach_genres([2, 3]).execute(&pool).await.unwrap();
+    assert_eq!(1, result.rows_affected());
+    assert_eq!(vec![1, 2, 3], genres(&novel).await);
+    let other = Novel { id: 2, author_id: 1, title: "Persuasion".into() };
+    assert!(genres(&other).await.is_empty());
+
+    let result = novel.detach_genres([1, 3]).execute(&pool).await.unwrap();
+    assert_eq!(2, result.rows_affected());
+    assert_eq!(vec![2], genres(&novel).await);
+    // nothing to insert or delete
+    novel.attach_genres([]).execute(&pool).await.unwrap();
+    assert_eq!(0, novel.detach_genres([]).execute(&pool).await.unwrap().rows_affected());
+
+    novel.sync_genres([1, 3], &pool).await.unwrap();
+    assert_eq!(vec![1, 3], genres(&novel).await);
+    novel.sync_genres([], &pool).await.unwrap();
+    assert!(genres(&novel).await.is_empty());
+}
+
+#[derive(Debug, Cherry, PartialEq)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql", has_many(Novel, foreign_key = "author_id"))]
+struct Author {
+    #[cherry(primary_key)]
+    id: u32,
+    name: String,
+}
+
+#[derive(Clone, Debug, Cherry)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+#[cherry(belongs_to(Author, foreign_key = "author_id"))]
+#[cherry(has_many(Chapter, foreign_key = "novel_id"))]
+#[cherry(many_to_many(Genre, through = "novel_genres", left = "novel_id", right = "genre_id"))]
+struct Novel {
+    #[cherry(primary_key)]
+    id: u32,
+    author_id: u32,
+    title: String,
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+#[cherry(belongs_to(Novel, foreign_key = "novel_id", name = "book"))]
+struct Chapter {
+    #[cherry(primary_key)]
+    id: u32,
+    novel_id: u32,
+    title: String,
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+#[cherry(belongs_to(Novel, foreign_key = "novel_id"))]
+struct Review {
+    #[cherry(primary_key)]
+    id: u32,
+    novel_id: Option<u32>,
+    text: String,
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "sqlite", verify = "tests/migrations.sql")]
+struct Genre {
+    #[cherry(primary_key)]
+    id: u32,
+    name: String,
+}
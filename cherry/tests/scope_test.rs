@@ -0,0 +1,114 @@
+use sqlx::Executor;
+
+use cherry::{Cherry, QueryError, TenantId};
+use cherry::clause::{End, UpdateSet, Where};
+use cherry::QueryExecutor;
+use cherry::sqlite::SqlitePool;
+
+async fn init() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let projects = vec![
+        Project { tenant_id: 1, id: 1, name: "apple".into(), archived: false },
+        Project { tenant_id: 1, id: 2, name: "banana".into(), archived: false },
+        Project { tenant_id: 1, id: 3, name: "cherry".into(), archived: true },
+        Project { tenant_id: 2, id: 1, name: "durian".into(), archived: false },
+    ];
+    // the tenant of every project
+    Project::insert_bulk(&projects).unscoped().execute(&pool).await.unwrap();
+    pool
+}
+
+fn names(projects: Vec<Project>) -> Vec<String> {
+    projects.into_iter().map(|p| p.name).collect()
+}
+
+#[async_std::test]
+async fn test_select() {
+    let pool = init().await;
+    let projects = TenantId(1_u32).scope(Project::select().all(&pool)).await.unwrap();
+    assert_eq!(vec!["apple", "banana"], names(projects));
+
+    // the tenant is bound after the other conditions, before the limit
+    let query = Project::select().and_eq("name", "durian").or_eq("id", 1).order_by_asc("id").limit(1);
+    let projects = TenantId(2_u32).scope(query.all(&pool)).await.unwrap();
+    assert_eq!(vec!["durian"], names(projects));
+
+    let projects = Project::select().unscoped().order_by_asc("name").all(&pool).await.unwrap();
+    assert_eq!(vec!["apple", "banana", "cherry", "durian"], names(projects));
+}
+
+#[async_std::test]
+async fn test_missing_tenant() {
+    let pool = init().await;
+    let err = Project::select().all(&pool).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::MissingTenant("project"))));
+    let err = Project::delete().execute(&pool).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::MissingTenant("project"))));
+    // the tenant of another type
+    let err = TenantId("1").scope(Project::select().all(&pool)).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::MissingTenant("project"))));
+
+    // not the tenant of the row
+    let project = Project { tenant_id: 1, id: 4, name: "fig".into(), archived: false };
+    let err = project.insert().execute(&pool).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::MissingTenant("project"))));
+    let projects = Project::select().unscoped().and_eq("id", 4).all(&pool).await.unwrap();
+    assert!(projects.is_empty());
+}
+
+#[async_std::test]
+async fn test_update_and_delete() {
+    let pool = init().await;
+    TenantId(2_u32).scope(async {
+        let r = Project::update().set("name", "elderberry").and_eq("id", 1).execute(&pool).await.unwrap();
+        assert_eq!(1, r.rows_affected());
+        let r = Project::delete().and_eq("id", 1).execute(&pool).await.unwrap();
+        assert_eq!(1, r.rows_affected());
+    }).await;
+
+    let projects = TenantId(1_u32).scope(Project::select().all(&pool)).await.unwrap();
+    assert_eq!(vec!["apple", "banana"], names(projects));
+}
+
+#[async_std::test]
+async fn test_unscoped_update_and_delete() {
+    let pool = init().await;
+    // every tenant and the archived projects
+    let r = Project::update().set("archived", true).unscoped().execute(&pool).await.unwrap();
+    assert_eq!(4, r.rows_affected());
+    let r = Project::delete().and_eq("id", 1).unscoped().execute(&pool).await.unwrap();
+    assert_eq!(2, r.rows_affected());
+
+    let projects = Project::select().unscoped().order_by_asc("name").all(&pool).await.unwrap();
+    assert_eq!(vec!["banana", "cherry"], names(projects));
+}
+
+#[async_std::test]
+async fn test_insert() {
+    let pool = init().await;
+    let project = Project { tenant_id: 1, id: 4, name: "fig".into(), archived: false };
+    let err = TenantId(2_u32).scope(async { project.insert().execute(&pool).await }).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::TenantMismatch("project"))));
+    let projects = [
+        Project { tenant_id: 2, id: 4, name: "fig".into(), archived: false },
+        Project { tenant_id: 1, id: 5, name: "grape".into(), archived: false },
+    ];
+    let err = TenantId(2_u32).scope(async { Project::insert_bulk(&projects).execute_chunked(&pool).await }).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::TenantMismatch("project"))));
+
+    TenantId(2_u32).scope(async { projects[0].insert().execute(&pool).await }).await.unwrap();
+    let projects = TenantId(2_u32).scope(Project::select().all(&pool)).await.unwrap();
+    assert_eq!(vec!["durian", "fig"], names(projects));
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "sqlite", scope = "archived = 0", verify = "tests/migrations.sql")]
+struct Project {
+    #[cherry(primary_key, tenant)]
+    tenant_id: u32,
+    #[cherry(primary_key)]
+    id: u32,
+    name: String,
+    archived: bool,
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use sqlx::Executor;
+
+use cherry::{Cherry, QueryError};
+use cherry::clause::{SelectColumn, Where};
+use cherry::QueryExecutor;
+use cherry::sqlite::SqlitePool;
+
+async fn init() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    pool
+}
+
+#[async_std::test]
+async fn test_explain_uses_index() {
+    let pool = init().await;
+    let plan = User::select().and_eq("id", 100).explain(&pool).await.unwrap();
+    plan.assert_uses_index(None);
+
+    let plan = User::select().and_eq("name", "Joe").explain(&pool).await.unwrap();
+    assert!(!plan.uses_index());
+}
+
+#[async_std::test]
+async fn test_explain_rows() {
+    let pool = init().await;
+    let plan = User::select().and_eq("id", 100).explain(&pool).await.unwrap();
+    assert_eq!(["SEARCH user USING PRIMARY KEY (id=?)"], plan.rows());
+    // the query plan of SQLite, it can't analyze
+    let plan = User::select().and_eq("name", "Joe").explain_analyze(&pool).await.unwrap();
+    assert_eq!(["SCAN user"], plan.rows());
+}
+
+#[async_std::test]
+async fn test_timeout() {
+    let pool = init().await;
+    User { id: 100, name: "Joe".into(), age: 25 }.insert().execute(&pool).await.unwrap();
+
+    let slow = "(WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c LIMIT 10000000) \
+        SELECT COUNT(*) FROM c)";
+    let err = User::select().column_raw(slow).timeout(Duration::from_millis(10))
+        .tuple::<(i64,), _>(&pool).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<QueryError>(), Some(QueryError::Timeout(_))));
+}
+
+#[derive(Debug, Cherry, Eq, PartialEq)]
+#[cherry(database = "sqlite")]
+struct User {
+    id: u32,
+    name: String,
+    age: u8,
+}
@@ -0,0 +1,92 @@
+use chrono::NaiveDateTime;
+use sqlx::Executor;
+
+use cherry::{Cherry, PrimaryKey};
+use cherry::clause::{UpdateSet, Where};
+use cherry::QueryExecutor;
+use cherry::sqlite::SqlitePool;
+
+async fn init() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.execute(include_str!("migrations.sql")).await.unwrap();
+    let comments = vec![
+        Comment { id: 1, body: "first".into(), deleted_at: None },
+        Comment { id: 2, body: "second".into(), deleted_at: None },
+        Comment { id: 3, body: "third".into(), deleted_at: None },
+    ];
+    Comment::insert_bulk(&comments).execute(&pool).await.unwrap();
+    pool
+}
+
+async fn ids(query: cherry::Query<'_, Comment, sqlx::Sqlite>, pool: &SqlitePool) -> Vec<u32> {
+    query.all(pool).await.unwrap().iter().map(|c| c.id).collect()
+}
+
+#[async_std::test]
+async fn test_delete() {
+    let pool = init().await;
+    assert_eq!(1, Comment::delete().and_eq("id", 1).execute(&pool).await.unwrap().rows_affected());
+    // deleted already
+    assert_eq!(0, Comment::delete().and_eq("id", 1).execute(&pool).await.unwrap().rows_affected());
+
+    assert_eq!(vec![2, 3], ids(Comment::select(), &pool).await);
+    assert_eq!(vec![1, 2, 3], ids(Comment::select().with_trashed(), &pool).await);
+    assert_eq!(vec![1], ids(Comment::select().only_trashed(), &pool).await);
+    let deleted = Comment::select().only_trashed().one(&pool).await.unwrap().unwrap();
+    assert!(deleted.deleted_at.is_some());
+
+    // the OR is kept inside the scope
+    let query = Comment::select().and_eq("id", 1).or_eq("id", 2);
+    assert_eq!(vec![2], ids(query, &pool).await);
+}
+
+#[async_std::test]
+async fn test_update_and_find() {
+    let pool = init().await;
+    let comment = Comment::find_or_fail(2, &pool).await.unwrap();
+    comment.delete_by_pk().execute(&pool).await.unwrap();
+    assert!(Comment::find(2).one(&pool).await.unwrap().is_none());
+    assert!(Comment::find(2).with_trashed().one(&pool).await.unwrap().is_some());
+
+    let r = Comment::update().set("body", "edited").execute(&pool).await.unwrap();
+    assert_eq!(2, r.rows_affected());
+    let r = Comment::update().set("body", "edited").with_trashed().execute(&pool).await.unwrap();
+    assert_eq!(3, r.rows_affected());
+}
+
+#[async_std::test]
+async fn test_save_keeps_deleted() {
+    let pool = init().await;
+    let stale = Comment::find_or_fail(1, &pool).await.unwrap();
+    Comment::delete().and_eq("id", 1).execute(&pool).await.unwrap();
+
+    let saved = Comment { body: "saved".into(), ..stale };
+    saved.save().execute(&pool).await.unwrap();
+    let comment = Comment::find(1).with_trashed().one(&pool).await.unwrap().unwrap();
+    assert_eq!("saved", comment.body);
+    // still trashed
+    assert!(comment.deleted_at.is_some());
+    assert!(Comment::find(1).one(&pool).await.unwrap().is_none());
+}
+
+#[async_std::test]
+async fn test_restore_and_force_delete() {
+    let pool = init().await;
+    Comment::delete().execute(&pool).await.unwrap();
+    assert!(ids(Comment::select(), &pool).await.is_empty());
+
+    assert_eq!(1, Comment::restore().and_eq("id", 3).execute(&pool).await.unwrap().rows_affected());
+    assert_eq!(vec![3], ids(Comment::select(), &pool).await);
+
+    assert_eq!(1, Comment::force_delete().and_eq("id", 1).execute(&pool).await.unwrap().rows_affected());
+    assert_eq!(vec![2, 3], ids(Comment::select().with_trashed(), &pool).await);
+}
+
+#[derive(Debug, Cherry)]
+#[cherry(database = "sqlite", soft_delete = "deleted_at", verify = "tests/migrations.sql")]
+struct Comment {
+    #[cherry(primary_key)]
+    id: u32,
+    body: String,
+    deleted_at: Option<NaiveDateTime>,
+}
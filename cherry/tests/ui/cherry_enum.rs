@@ -0,0 +1,26 @@
+use cherry::CherryEnum;
+
+#[derive(CherryEnum)]
+struct Status {
+    active: bool,
+}
+
+#[derive(CherryEnum)]
+enum Shape {
+    Circle(u32),
+    Square,
+}
+
+#[derive(CherryEnum)]
+#[cherry(repr = "u8")]
+enum Level {
+    Low,
+}
+
+#[derive(CherryEnum)]
+enum Color {
+    #[cherry(name = "RED")]
+    Red,
+}
+
+fn main() {}
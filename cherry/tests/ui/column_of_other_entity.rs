@@ -0,0 +1,24 @@
+use cherry::Cherry;
+use cherry::clause::Where;
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct User {
+    #[cherry(primary_key)]
+    id: u32,
+    name: String,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Post {
+    #[cherry(primary_key)]
+    id: u32,
+    title: String,
+}
+
+fn main() {
+    let _ = User::select().and_eq(User::NAME, "Joe");
+    // a column of `post`, not in the `user` table
+    let _ = User::select().and_eq(Post::TITLE, "Hello");
+}
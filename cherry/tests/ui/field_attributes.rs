@@ -0,0 +1,52 @@
+use cherry::Cherry;
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Skipped {
+    #[cherry(primary_key, skip)]
+    id: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Versions {
+    #[cherry(version)]
+    version: u32,
+    #[cherry(version)]
+    revision: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Timestamps {
+    #[cherry(primary_key, created_at)]
+    created_at: String,
+    #[cherry(created_at, updated_at)]
+    updated_at: String,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite", soft_delete = "deleted_at")]
+struct SoftDelete {
+    id: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Tenant {
+    #[cherry(tenant, skip)]
+    tenant_id: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Prefix {
+    #[cherry(prefix = "addr_")]
+    street: String,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Tuple(u32);
+
+fn main() {}
@@ -0,0 +1,19 @@
+use cherry::Cherry;
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+enum Status {
+    Active,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+union Value {
+    int: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Empty;
+
+fn main() {}
@@ -0,0 +1,16 @@
+use cherry::{Cherry, Tracked};
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct User {
+    #[cherry(primary_key)]
+    id: u32,
+    name: String,
+}
+
+fn main() {
+    let mut user = Tracked::new(User { id: 1, name: "Joe".into() });
+    user.set(User::NAME, "Joseph".to_string());
+    // updated by its key, which can't be changed
+    user.set(User::ID, 2);
+}
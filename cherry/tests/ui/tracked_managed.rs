@@ -0,0 +1,21 @@
+use cherry::{Cherry, Tracked};
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Post {
+    #[cherry(primary_key)]
+    id: u32,
+    title: String,
+    #[cherry(generated)]
+    created_at: String,
+    #[cherry(version)]
+    version: u32,
+}
+
+fn main() {
+    let mut post = Tracked::new(Post { id: 1, title: "Draft".into(), created_at: String::new(), version: 1 });
+    post.set(Post::TITLE, "Final".to_string());
+    // never set by `update()`
+    post.set(Post::CREATED_AT, String::new());
+    post.set(Post::VERSION, 2);
+}
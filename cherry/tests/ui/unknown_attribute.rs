@@ -0,0 +1,40 @@
+use cherry::Cherry;
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite", tabel = "user")]
+struct User {
+    id: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite")]
+struct Post {
+    #[cherry(primary_key, colum = "post_id")]
+    id: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite", rename_all = "lowercase")]
+struct Tag {
+    id: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite, oracle")]
+struct Comment {
+    id: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite", verify = true)]
+struct Like {
+    id: u32,
+}
+
+#[derive(Cherry)]
+#[cherry(database = "sqlite", scope = "owner = ?")]
+struct Project {
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+// The compile errors of the derives, `TRYBUILD=overwrite` updates the `.stderr` snapshots.
+#[test]
+fn test_compile_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}